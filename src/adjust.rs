@@ -0,0 +1,193 @@
+// Boost/Apache2 License
+
+//! Deriving new colors from existing ones.
+
+use crate::space::clamp;
+use crate::{LinearRgb, Oklab, SysColor};
+
+/// The color space in which colors are blended.
+///
+/// The result of mixing two colors depends on the space the mixing happens in. Blending gamma
+/// encoded sRGB matches what most legacy drawing code does, blending linear light matches how
+/// light physically combines, and blending in Oklab gives perceptually even steps.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorSpace {
+    /// Gamma-encoded sRGB, as stored in a [`SysColor`].
+    #[default]
+    Srgb,
+
+    /// Linear-light sRGB.
+    LinearRgb,
+
+    /// The Oklab perceptual color space.
+    Oklab,
+}
+
+impl ColorSpace {
+    /// Convert a color into coordinates in this space.
+    fn coords(self, color: SysColor) -> [f32; 3] {
+        match self {
+            ColorSpace::Srgb => [color.red(), color.green(), color.blue()].map(f32::from),
+            ColorSpace::LinearRgb => {
+                let LinearRgb { red, green, blue } = color.into();
+                [red, green, blue]
+            }
+            ColorSpace::Oklab => {
+                let Oklab { l, a, b } = color.into();
+                [l, a, b]
+            }
+        }
+    }
+
+    /// Convert coordinates in this space back into a color.
+    fn color(self, [x, y, z]: [f32; 3]) -> SysColor {
+        match self {
+            ColorSpace::Srgb => {
+                let channel = |c: f32| libm::roundf(c.clamp(0.0, 255.0)) as u8;
+                SysColor::from_rgb(channel(x), channel(y), channel(z))
+            }
+            ColorSpace::LinearRgb => LinearRgb {
+                red: x,
+                green: y,
+                blue: z,
+            }
+            .into(),
+            ColorSpace::Oklab => Oklab { l: x, a: y, b: z }.into(),
+        }
+    }
+
+    /// Interpolate between two colors in this space, without clamping the factor.
+    pub(crate) fn lerp(self, from: SysColor, to: SysColor, t: f32) -> SysColor {
+        let (from, to) = (self.coords(from), self.coords(to));
+        let mut result = [0.0; 3];
+
+        for ((out, from), to) in result.iter_mut().zip(from).zip(to) {
+            *out = from + (to - from) * t;
+        }
+
+        self.color(result)
+    }
+}
+
+/// Pure white.
+pub(crate) const WHITE: SysColor = SysColor::from_rgb(255, 255, 255);
+
+/// Pure black.
+pub(crate) const BLACK: SysColor = SysColor::from_rgb(0, 0, 0);
+
+impl SysColor {
+    /// Mix this color with another one.
+    ///
+    /// `t` ranges from `0.0`, which returns this color, to `1.0`, which returns `other`. Values
+    /// outside of that range are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorSpace, SysColor};
+    ///
+    /// let black = SysColor::from_rgb(0, 0, 0);
+    /// let white = SysColor::from_rgb(255, 255, 255);
+    ///
+    /// assert_eq!(black.mix(white, 0.5, ColorSpace::Srgb).to_string(), "#808080");
+    /// assert_eq!(black.mix(white, 0.5, ColorSpace::LinearRgb).to_string(), "#BCBCBC");
+    /// ```
+    pub fn mix(self, other: SysColor, t: f32, space: ColorSpace) -> SysColor {
+        space.lerp(self, other, clamp(t))
+    }
+
+    /// Mix this color towards white by the given amount, from `0.0` to `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorSpace, SysColor};
+    ///
+    /// let face = SysColor::from_rgb(212, 208, 200);
+    /// let light = face.lighten(0.5, ColorSpace::Srgb);
+    /// assert_eq!(light.to_string(), "#EAE8E4");
+    /// ```
+    pub fn lighten(self, amount: f32, space: ColorSpace) -> SysColor {
+        self.mix(WHITE, amount, space)
+    }
+
+    /// Mix this color towards black by the given amount, from `0.0` to `1.0`.
+    pub fn darken(self, amount: f32, space: ColorSpace) -> SysColor {
+        self.mix(BLACK, amount, space)
+    }
+
+    /// Move this color away from its grayscale equivalent by the given amount.
+    ///
+    /// An amount of `1.0` doubles the distance from gray. Channels that leave the sRGB gamut are
+    /// clamped.
+    pub fn saturate(self, amount: f32, space: ColorSpace) -> SysColor {
+        space.lerp(self.grayscale(space), self, 1.0 + amount.max(0.0))
+    }
+
+    /// Mix this color towards its grayscale equivalent by the given amount, from `0.0` to `1.0`.
+    pub fn desaturate(self, amount: f32, space: ColorSpace) -> SysColor {
+        self.mix(self.grayscale(space), amount, space)
+    }
+
+    /// Get the grayscale equivalent of this color.
+    ///
+    /// In [`ColorSpace::Srgb`] this uses the Rec. 709 luma of the encoded channels, in
+    /// [`ColorSpace::LinearRgb`] the relative luminance, and in [`ColorSpace::Oklab`] the
+    /// perceived lightness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorSpace, SysColor};
+    ///
+    /// let gray = SysColor::from_rgb(0, 120, 215).grayscale(ColorSpace::Oklab);
+    /// assert_eq!(gray.red(), gray.green());
+    /// assert_eq!(gray.green(), gray.blue());
+    /// ```
+    pub fn grayscale(self, space: ColorSpace) -> SysColor {
+        let [x, y, z] = space.coords(self);
+
+        let coords = match space {
+            ColorSpace::Srgb | ColorSpace::LinearRgb => {
+                let luma = 0.2126 * x + 0.7152 * y + 0.0722 * z;
+                [luma; 3]
+            }
+            ColorSpace::Oklab => [x, 0.0, 0.0],
+        };
+
+        space.color(coords)
+    }
+
+    /// Invert every channel of this color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// assert_eq!(SysColor::from_rgb(10, 36, 106).invert().to_string(), "#F5DB95");
+    /// ```
+    pub fn invert(self) -> SysColor {
+        SysColor::from_rgb(!self.red(), !self.green(), !self.blue())
+    }
+
+    /// Composite this color with the given opacity over an opaque background.
+    ///
+    /// `alpha` ranges from `0.0`, which returns `background`, to `1.0`, which returns this color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorSpace, SysColor};
+    ///
+    /// let highlight = SysColor::from_rgb(0, 120, 215);
+    /// let window = SysColor::from_rgb(255, 255, 255);
+    ///
+    /// let hover = highlight.over(window, 0.25, ColorSpace::Srgb);
+    /// assert_eq!(hover.to_string(), "#BFDDF5");
+    /// ```
+    pub fn over(self, background: SysColor, alpha: f32, space: ColorSpace) -> SysColor {
+        background.mix(self, alpha, space)
+    }
+}
@@ -0,0 +1,261 @@
+// Boost/Apache2 License
+
+//! Colors with an alpha channel.
+
+use crate::{ColorSpace, SysColor};
+
+use core::fmt;
+
+/// A color with a straight (non-premultiplied) alpha channel.
+///
+/// `GetSysColor` returns opaque colors, but newer sources such as DWM colorization return
+/// translucent colors in `0xAARRGGBB` form. This type bridges the two.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rgba {
+    /// The red component.
+    pub red: u8,
+
+    /// The green component.
+    pub green: u8,
+
+    /// The blue component.
+    pub blue: u8,
+
+    /// The alpha component, where `0` is transparent and `255` is opaque.
+    pub alpha: u8,
+}
+
+/// A color whose components have been multiplied by its alpha channel.
+///
+/// This is the form most compositors work in. Convert to it with [`Rgba::premultiply`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PremultipliedRgba {
+    /// The red component, multiplied by alpha.
+    pub red: u8,
+
+    /// The green component, multiplied by alpha.
+    pub green: u8,
+
+    /// The blue component, multiplied by alpha.
+    pub blue: u8,
+
+    /// The alpha component, where `0` is transparent and `255` is opaque.
+    pub alpha: u8,
+}
+
+impl Rgba {
+    /// Create a color from its red, green, blue and alpha components.
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Rgba {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Create a color from a raw value in `0xAARRGGBB` form.
+    ///
+    /// This is the form returned by `DwmGetColorizationColor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::Rgba;
+    ///
+    /// let color = Rgba::from_argb(0xC4_12_34_56);
+    /// assert_eq!(color, Rgba::new(0x12, 0x34, 0x56, 0xC4));
+    /// assert_eq!(color.to_argb(), 0xC4_12_34_56);
+    /// ```
+    pub const fn from_argb(raw: u32) -> Self {
+        Rgba::new(
+            (raw >> 16) as u8,
+            (raw >> 8) as u8,
+            raw as u8,
+            (raw >> 24) as u8,
+        )
+    }
+
+    /// Get the raw value of this color in `0xAARRGGBB` form.
+    pub const fn to_argb(self) -> u32 {
+        (self.alpha as u32) << 24
+            | (self.red as u32) << 16
+            | (self.green as u32) << 8
+            | self.blue as u32
+    }
+
+    /// Get the color without its alpha channel.
+    pub const fn color(self) -> SysColor {
+        SysColor::from_rgb(self.red, self.green, self.blue)
+    }
+
+    /// Tell whether this color is fully opaque.
+    pub const fn is_opaque(self) -> bool {
+        self.alpha == u8::MAX
+    }
+
+    /// Multiply the color components by the alpha channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{PremultipliedRgba, Rgba};
+    ///
+    /// let color = Rgba::new(255, 128, 0, 128);
+    /// assert_eq!(color.premultiply(), PremultipliedRgba::new(128, 64, 0, 128));
+    /// ```
+    pub const fn premultiply(self) -> PremultipliedRgba {
+        PremultipliedRgba::new(
+            multiply(self.red, self.alpha),
+            multiply(self.green, self.alpha),
+            multiply(self.blue, self.alpha),
+            self.alpha,
+        )
+    }
+
+    /// Composite this color over an opaque background.
+    ///
+    /// See [`SysColor::over`] for how the color space is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorSpace, Rgba, SysColor};
+    ///
+    /// let white = SysColor::from_rgb(255, 255, 255);
+    ///
+    /// assert_eq!(Rgba::new(0, 0, 0, 255).over(white, ColorSpace::Srgb), SysColor::from_rgb(0, 0, 0));
+    /// assert_eq!(Rgba::new(0, 0, 0, 0).over(white, ColorSpace::Srgb), white);
+    /// ```
+    pub fn over(self, background: SysColor, space: ColorSpace) -> SysColor {
+        self.color()
+            .over(background, self.alpha as f32 / u8::MAX as f32, space)
+    }
+}
+
+impl PremultipliedRgba {
+    /// Create a color from its premultiplied red, green and blue components and its alpha.
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        PremultipliedRgba {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Create a color from a raw premultiplied value in `0xAARRGGBB` form.
+    pub const fn from_argb(raw: u32) -> Self {
+        PremultipliedRgba::new(
+            (raw >> 16) as u8,
+            (raw >> 8) as u8,
+            raw as u8,
+            (raw >> 24) as u8,
+        )
+    }
+
+    /// Get the raw value of this color in `0xAARRGGBB` form.
+    pub const fn to_argb(self) -> u32 {
+        (self.alpha as u32) << 24
+            | (self.red as u32) << 16
+            | (self.green as u32) << 8
+            | self.blue as u32
+    }
+
+    /// Divide the color components by the alpha channel.
+    ///
+    /// A fully transparent color has no recoverable components and becomes transparent black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{PremultipliedRgba, Rgba};
+    ///
+    /// let color = PremultipliedRgba::new(128, 64, 0, 128);
+    /// assert_eq!(color.unpremultiply(), Rgba::new(255, 128, 0, 128));
+    /// assert_eq!(PremultipliedRgba::new(0, 0, 0, 0).unpremultiply(), Rgba::new(0, 0, 0, 0));
+    /// ```
+    pub const fn unpremultiply(self) -> Rgba {
+        Rgba::new(
+            divide(self.red, self.alpha),
+            divide(self.green, self.alpha),
+            divide(self.blue, self.alpha),
+            self.alpha,
+        )
+    }
+}
+
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{:02X}{:02X}{:02X}{:02X}",
+            self.red, self.green, self.blue, self.alpha
+        )
+    }
+}
+
+impl fmt::Display for PremultipliedRgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{:02X}{:02X}{:02X}{:02X}",
+            self.red, self.green, self.blue, self.alpha
+        )
+    }
+}
+
+impl From<SysColor> for Rgba {
+    fn from(color: SysColor) -> Self {
+        Rgba::new(color.red(), color.green(), color.blue(), u8::MAX)
+    }
+}
+
+impl From<SysColor> for PremultipliedRgba {
+    fn from(color: SysColor) -> Self {
+        Rgba::from(color).premultiply()
+    }
+}
+
+impl From<Rgba> for PremultipliedRgba {
+    fn from(color: Rgba) -> Self {
+        color.premultiply()
+    }
+}
+
+impl From<PremultipliedRgba> for Rgba {
+    fn from(color: PremultipliedRgba) -> Self {
+        color.unpremultiply()
+    }
+}
+
+impl From<Rgba> for [u8; 4] {
+    fn from(color: Rgba) -> Self {
+        [color.red, color.green, color.blue, color.alpha]
+    }
+}
+
+impl From<[u8; 4]> for Rgba {
+    fn from([red, green, blue, alpha]: [u8; 4]) -> Self {
+        Rgba::new(red, green, blue, alpha)
+    }
+}
+
+/// Multiply a component by alpha, rounding to the nearest value.
+const fn multiply(component: u8, alpha: u8) -> u8 {
+    ((component as u32 * alpha as u32 + 127) / 255) as u8
+}
+
+/// Divide a premultiplied component by alpha, rounding to the nearest value.
+const fn divide(component: u8, alpha: u8) -> u8 {
+    if alpha == 0 {
+        return 0;
+    }
+
+    let value = (component as u32 * 255 + alpha as u32 / 2) / alpha as u32;
+    if value > 255 {
+        255
+    } else {
+        value as u8
+    }
+}
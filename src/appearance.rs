@@ -0,0 +1,507 @@
+// Boost/Apache2 License
+
+//! Reading and writing the schemes of the classic Appearance dialog.
+
+use crate::{SysColor, SysColorIndex, SysColorPalette};
+
+use core::fmt;
+
+/// The only supported version of the scheme layout.
+const VERSION: i16 = 2;
+
+/// The size of `NONCLIENTMETRICSW`, without the `iPaddedBorderWidth` added in Windows Vista.
+const METRICS_SIZE: u32 = 500;
+
+/// The number of colors stored in a scheme, `COLOR_SCROLLBAR` through
+/// `COLOR_GRADIENTINACTIVECAPTION`.
+const COLOR_COUNT: usize = 29;
+
+/// The slot of the color that has no [`SysColorIndex`].
+const BUTTON_ALTERNATE_FACE: usize = 25;
+
+/// The number of UTF-16 code units in a font face name, including the terminator.
+const FACE_NAME_LEN: usize = 32;
+
+/// A scheme of the classic Appearance dialog.
+///
+/// Windows stores these as binary values under `HKCU\Control Panel\Appearance\Schemes`, in the
+/// layout of the `SCHEMEDATA` structure used by the Display control panel: a version number,
+/// a `NONCLIENTMETRICSW` structure, the `LOGFONTW` of icon titles and the colors indexed by
+/// their `COLOR_*` value.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AppearanceScheme {
+    /// The colors of the scheme.
+    ///
+    /// Schemes store the colors from `COLOR_SCROLLBAR` through
+    /// `COLOR_GRADIENTINACTIVECAPTION`, so [`SysColorIndex::MenuHighlight`] and
+    /// [`SysColorIndex::MenuBar`] are never present.
+    pub palette: SysColorPalette,
+
+    /// The raw `COLORREF` stored in slot 25, known in the registry as `ButtonAlternateFace`.
+    ///
+    /// No system color uses this slot, but it is kept so that schemes can be written back
+    /// unchanged.
+    pub button_alternate_face: u32,
+
+    /// The two bytes of padding that follow the version number.
+    ///
+    /// They carry no meaning, but are kept so that schemes can be written back unchanged.
+    pub padding: u16,
+
+    /// The sizes and fonts of window frames, captions and menus.
+    pub metrics: NonClientMetrics,
+
+    /// The font of icon titles.
+    pub icon_font: LogFont,
+}
+
+/// The sizes and fonts of the nonclient area of windows, as in `NONCLIENTMETRICSW`.
+///
+/// Sizes are in pixels.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NonClientMetrics {
+    /// The thickness of sizing borders.
+    pub border_width: i32,
+
+    /// The width of vertical scroll bars.
+    pub scroll_width: i32,
+
+    /// The height of horizontal scroll bars.
+    pub scroll_height: i32,
+
+    /// The width of caption buttons.
+    pub caption_width: i32,
+
+    /// The height of caption buttons.
+    pub caption_height: i32,
+
+    /// The font of captions.
+    pub caption_font: LogFont,
+
+    /// The width of small caption buttons.
+    pub small_caption_width: i32,
+
+    /// The height of small captions.
+    pub small_caption_height: i32,
+
+    /// The font of small captions.
+    pub small_caption_font: LogFont,
+
+    /// The width of menu bar buttons.
+    pub menu_width: i32,
+
+    /// The height of menu bars.
+    pub menu_height: i32,
+
+    /// The font of menu bars.
+    pub menu_font: LogFont,
+
+    /// The font of status bars and tooltips.
+    pub status_font: LogFont,
+
+    /// The font of message boxes.
+    pub message_font: LogFont,
+}
+
+/// A font description, as in `LOGFONTW`.
+///
+/// The byte fields hold the raw values of the matching `LOGFONTW` fields.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LogFont {
+    /// The height of the character cell or, if negative, of the characters.
+    pub height: i32,
+
+    /// The average width of characters, or `0` to match the height.
+    pub width: i32,
+
+    /// The angle of text lines, in tenths of a degree.
+    pub escapement: i32,
+
+    /// The angle of each character's baseline, in tenths of a degree.
+    pub orientation: i32,
+
+    /// The weight of the font, from `0` to `1000`, where `400` is normal and `700` is bold.
+    pub weight: i32,
+
+    /// Whether the font is italic.
+    pub italic: u8,
+
+    /// Whether the font is underlined.
+    pub underline: u8,
+
+    /// Whether the font is struck out.
+    pub strike_out: u8,
+
+    /// The character set.
+    pub char_set: u8,
+
+    /// The output precision.
+    pub out_precision: u8,
+
+    /// The clipping precision.
+    pub clip_precision: u8,
+
+    /// The output quality.
+    pub quality: u8,
+
+    /// The pitch and family.
+    pub pitch_and_family: u8,
+
+    /// The name of the typeface, as null-terminated UTF-16.
+    pub face_name: [u16; FACE_NAME_LEN],
+}
+
+impl LogFont {
+    /// Get the name of the typeface.
+    ///
+    /// The name is displayed up to its null terminator, with invalid UTF-16 replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::LogFont;
+    ///
+    /// let mut font = LogFont::default();
+    /// for (slot, unit) in font.face_name.iter_mut().zip("Tahoma".encode_utf16()) {
+    ///     *slot = unit;
+    /// }
+    ///
+    /// assert_eq!(font.face_name().to_string(), "Tahoma");
+    /// ```
+    pub fn face_name(&self) -> FaceName<'_> {
+        FaceName {
+            units: &self.face_name,
+        }
+    }
+}
+
+/// The name of a typeface, returned by [`LogFont::face_name`].
+#[derive(Debug, Copy, Clone)]
+pub struct FaceName<'a> {
+    units: &'a [u16; FACE_NAME_LEN],
+}
+
+impl fmt::Display for FaceName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.units.iter().copied().take_while(|&unit| unit != 0);
+
+        for ch in char::decode_utf16(units) {
+            fmt::Write::write_char(f, ch.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AppearanceScheme {
+    /// The size of an encoded scheme, in bytes.
+    pub const SIZE: usize = 712;
+
+    /// Decode a scheme from the bytes of its registry value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{AppearanceScheme, ColorScheme, SysColorIndex};
+    ///
+    /// let scheme = AppearanceScheme {
+    ///     palette: *ColorScheme::Brick.palette(),
+    ///     ..AppearanceScheme::default()
+    /// };
+    ///
+    /// let bytes = scheme.encode();
+    /// assert_eq!(bytes.len(), AppearanceScheme::SIZE);
+    ///
+    /// let decoded = AppearanceScheme::decode(&bytes).unwrap();
+    /// assert_eq!(decoded.palette.get(SysColorIndex::ButtonFace), scheme.palette.get(SysColorIndex::ButtonFace));
+    /// assert_eq!(decoded.palette.get(SysColorIndex::MenuBar), None);
+    /// assert_eq!(decoded.encode(), bytes);
+    ///
+    /// // Unused data is kept as well.
+    /// let mut bytes = bytes;
+    /// bytes[2] = 0xAB;
+    /// assert_eq!(AppearanceScheme::decode(&bytes).unwrap().encode(), bytes);
+    /// ```
+    pub fn decode(bytes: &[u8]) -> Result<Self, AppearanceError> {
+        if bytes.len() != AppearanceScheme::SIZE {
+            return Err(AppearanceErrorKind::InvalidLength.into());
+        }
+
+        let mut reader = Reader { bytes };
+
+        let version = reader.i16();
+        if version != VERSION {
+            return Err(AppearanceErrorKind::UnsupportedVersion(version).into());
+        }
+        let padding = reader.u16();
+
+        if reader.u32() != METRICS_SIZE {
+            return Err(AppearanceErrorKind::InvalidLength.into());
+        }
+
+        let metrics = NonClientMetrics {
+            border_width: reader.i32(),
+            scroll_width: reader.i32(),
+            scroll_height: reader.i32(),
+            caption_width: reader.i32(),
+            caption_height: reader.i32(),
+            caption_font: reader.font(),
+            small_caption_width: reader.i32(),
+            small_caption_height: reader.i32(),
+            small_caption_font: reader.font(),
+            menu_width: reader.i32(),
+            menu_height: reader.i32(),
+            menu_font: reader.font(),
+            status_font: reader.font(),
+            message_font: reader.font(),
+        };
+        let icon_font = reader.font();
+
+        let mut palette = SysColorPalette::new();
+        let mut button_alternate_face = 0;
+
+        for slot in 0..COLOR_COUNT {
+            let raw = reader.u32();
+
+            if slot == BUTTON_ALTERNATE_FACE {
+                button_alternate_face = raw;
+                continue;
+            }
+
+            // Every other slot below the color count has an index.
+            let index = match SysColorIndex::from_raw(slot as i32) {
+                Some(index) => index,
+                None => continue,
+            };
+            let color =
+                SysColor::from_colorref(raw).ok_or(AppearanceErrorKind::ReservedByte(index))?;
+            palette.set(index, Some(color));
+        }
+
+        Ok(AppearanceScheme {
+            palette,
+            button_alternate_face,
+            padding,
+            metrics,
+            icon_font,
+        })
+    }
+
+    /// Encode this scheme into the bytes of its registry value.
+    ///
+    /// Colors missing from the palette are written as black.
+    pub fn encode(&self) -> [u8; AppearanceScheme::SIZE] {
+        let mut writer = Writer {
+            bytes: [0; AppearanceScheme::SIZE],
+            position: 0,
+        };
+
+        writer.i16(VERSION);
+        writer.u16(self.padding);
+        writer.u32(METRICS_SIZE);
+
+        let metrics = &self.metrics;
+        writer.i32(metrics.border_width);
+        writer.i32(metrics.scroll_width);
+        writer.i32(metrics.scroll_height);
+        writer.i32(metrics.caption_width);
+        writer.i32(metrics.caption_height);
+        writer.font(&metrics.caption_font);
+        writer.i32(metrics.small_caption_width);
+        writer.i32(metrics.small_caption_height);
+        writer.font(&metrics.small_caption_font);
+        writer.i32(metrics.menu_width);
+        writer.i32(metrics.menu_height);
+        writer.font(&metrics.menu_font);
+        writer.font(&metrics.status_font);
+        writer.font(&metrics.message_font);
+        writer.font(&self.icon_font);
+
+        for slot in 0..COLOR_COUNT {
+            let raw = if slot == BUTTON_ALTERNATE_FACE {
+                self.button_alternate_face
+            } else {
+                SysColorIndex::from_raw(slot as i32)
+                    .and_then(|index| self.palette.get(index))
+                    .map_or(0, SysColor::raw)
+            };
+
+            writer.u32(raw);
+        }
+
+        writer.bytes
+    }
+}
+
+/// The error returned when decoding an [`AppearanceScheme`] fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AppearanceError {
+    kind: AppearanceErrorKind,
+}
+
+impl AppearanceError {
+    /// Get the reason that decoding failed.
+    pub fn kind(&self) -> AppearanceErrorKind {
+        self.kind
+    }
+}
+
+impl From<AppearanceErrorKind> for AppearanceError {
+    fn from(kind: AppearanceErrorKind) -> Self {
+        AppearanceError { kind }
+    }
+}
+
+impl fmt::Display for AppearanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AppearanceErrorKind::InvalidLength => write!(
+                f,
+                "appearance scheme must be {} bytes with {}-byte metrics",
+                AppearanceScheme::SIZE,
+                METRICS_SIZE
+            ),
+            AppearanceErrorKind::UnsupportedVersion(version) => {
+                write!(f, "unsupported appearance scheme version: {}", version)
+            }
+            AppearanceErrorKind::ReservedByte(index) => write!(
+                f,
+                "the high byte of the {} color must be zero",
+                index.name()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AppearanceError {}
+
+/// The reason that decoding an [`AppearanceScheme`] failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AppearanceErrorKind {
+    /// The data or the metrics it contains had the wrong size.
+    InvalidLength,
+
+    /// The scheme had a version other than `2`.
+    UnsupportedVersion(i16),
+
+    /// A color had a nonzero reserved high byte.
+    ReservedByte(SysColorIndex),
+}
+
+/// Reads little-endian values from a scheme whose length has been checked.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    /// Read the next `N` bytes.
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut value = [0; N];
+        value.copy_from_slice(&self.bytes[..N]);
+        self.bytes = &self.bytes[N..];
+        value
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.take::<1>()[0]
+    }
+
+    fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.take())
+    }
+
+    fn i16(&mut self) -> i16 {
+        i16::from_le_bytes(self.take())
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    fn i32(&mut self) -> i32 {
+        i32::from_le_bytes(self.take())
+    }
+
+    /// Read a `LOGFONTW`.
+    fn font(&mut self) -> LogFont {
+        let mut font = LogFont {
+            height: self.i32(),
+            width: self.i32(),
+            escapement: self.i32(),
+            orientation: self.i32(),
+            weight: self.i32(),
+            italic: self.u8(),
+            underline: self.u8(),
+            strike_out: self.u8(),
+            char_set: self.u8(),
+            out_precision: self.u8(),
+            clip_precision: self.u8(),
+            quality: self.u8(),
+            pitch_and_family: self.u8(),
+            face_name: [0; FACE_NAME_LEN],
+        };
+
+        for unit in font.face_name.iter_mut() {
+            *unit = self.u16();
+        }
+
+        font
+    }
+}
+
+/// Writes little-endian values into an encoded scheme.
+struct Writer {
+    bytes: [u8; AppearanceScheme::SIZE],
+    position: usize,
+}
+
+impl Writer {
+    /// Write the given bytes.
+    fn put(&mut self, value: &[u8]) {
+        self.bytes[self.position..self.position + value.len()].copy_from_slice(value);
+        self.position += value.len();
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.put(&[value]);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.put(&value.to_le_bytes());
+    }
+
+    fn i16(&mut self, value: i16) {
+        self.put(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.put(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.put(&value.to_le_bytes());
+    }
+
+    /// Write a `LOGFONTW`.
+    fn font(&mut self, font: &LogFont) {
+        self.i32(font.height);
+        self.i32(font.width);
+        self.i32(font.escapement);
+        self.i32(font.orientation);
+        self.i32(font.weight);
+        self.u8(font.italic);
+        self.u8(font.underline);
+        self.u8(font.strike_out);
+        self.u8(font.char_set);
+        self.u8(font.out_precision);
+        self.u8(font.clip_precision);
+        self.u8(font.quality);
+        self.u8(font.pitch_and_family);
+
+        for &unit in font.face_name.iter() {
+            self.u16(unit);
+        }
+    }
+}
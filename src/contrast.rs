@@ -0,0 +1,499 @@
+// Boost/Apache2 License
+
+//! Contrast and legibility checks between system colors.
+
+use crate::adjust::{BLACK, WHITE};
+use crate::{ColorSpace, LinearRgb, SysColor, SysColorIndex, SysColorPalette};
+
+use core::iter::FusedIterator;
+use core::slice;
+
+impl SysColor {
+    /// Get the relative luminance of this color, as defined by WCAG 2.
+    ///
+    /// The result ranges from `0.0` for black to `1.0` for white.
+    pub fn relative_luminance(self) -> f32 {
+        let LinearRgb { red, green, blue } = self.into();
+        0.2126 * red + 0.7152 * green + 0.0722 * blue
+    }
+
+    /// Get the WCAG 2 contrast ratio between this color and another one.
+    ///
+    /// The result ranges from `1.0` for identical colors to `21.0` for black and white, and does
+    /// not depend on which color is the foreground.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// let black = SysColor::from_rgb(0, 0, 0);
+    /// let white = SysColor::from_rgb(255, 255, 255);
+    /// assert!((black.contrast_ratio(white) - 21.0).abs() < 0.01);
+    /// ```
+    pub fn contrast_ratio(self, other: SysColor) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Get the APCA lightness contrast (Lc) of this color as text on a background.
+    ///
+    /// This implements the APCA-W3 0.0.98G-4g algorithm proposed for WCAG 3. Unlike the WCAG 2
+    /// ratio, the result depends on which color is the text. It is positive for dark text on a
+    /// light background and negative for light text on a dark background, and its magnitude
+    /// ranges up to about 108.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// let black = SysColor::from_rgb(0, 0, 0);
+    /// let white = SysColor::from_rgb(255, 255, 255);
+    ///
+    /// assert!((black.apca_contrast(white) - 106.04).abs() < 0.1);
+    /// assert!((white.apca_contrast(black) + 107.88).abs() < 0.1);
+    /// ```
+    pub fn apca_contrast(self, background: SysColor) -> f32 {
+        let text = soft_clamp(apca_luminance(self));
+        let background = soft_clamp(apca_luminance(background));
+
+        if (background - text).abs() < APCA_DELTA_Y_MIN {
+            return 0.0;
+        }
+
+        let lc = if background > text {
+            let sapc = (libm::powf(background, 0.56) - libm::powf(text, 0.57)) * 1.14;
+            if sapc < APCA_LO_CLIP {
+                0.0
+            } else {
+                sapc - APCA_OFFSET
+            }
+        } else {
+            let sapc = (libm::powf(background, 0.65) - libm::powf(text, 0.62)) * 1.14;
+            if sapc > -APCA_LO_CLIP {
+                0.0
+            } else {
+                sapc + APCA_OFFSET
+            }
+        };
+
+        lc * 100.0
+    }
+
+    /// Pick the candidate text color with the highest contrast ratio against this background.
+    ///
+    /// Returns `None` if there are no candidates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// let black = SysColor::from_rgb(0, 0, 0);
+    /// let white = SysColor::from_rgb(255, 255, 255);
+    /// let highlight = SysColor::from_rgb(10, 36, 106);
+    ///
+    /// assert_eq!(highlight.readable_text(&[black, white]), Some(white));
+    /// ```
+    pub fn readable_text(self, candidates: &[SysColor]) -> Option<SysColor> {
+        candidates
+            .iter()
+            .copied()
+            .fold(None, |best, candidate| match best {
+                Some(best) if self.contrast_ratio(best) >= self.contrast_ratio(candidate) => {
+                    Some(best)
+                }
+                _ => Some(candidate),
+            })
+    }
+
+    /// Adjust the lightness of this text color until it reaches a contrast ratio against the
+    /// background.
+    ///
+    /// The color is moved towards the extreme on its own side of the background, white for
+    /// light text and black for dark text, so that its [polarity](SysColor::polarity) is kept.
+    /// When that extreme cannot meet `min_ratio`, the color is moved towards the other one
+    /// instead. Along that path, the smallest step that meets `min_ratio` is taken. If neither
+    /// extreme meets it, the one that contrasts more with the background is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{Polarity, SysColor};
+    ///
+    /// let gray = SysColor::from_rgb(109, 109, 109);
+    /// let face = SysColor::from_rgb(240, 240, 240);
+    ///
+    /// let text = gray.with_min_contrast(face, 7.0);
+    /// assert!(text.contrast_ratio(face) >= 7.0);
+    ///
+    /// // Light text stays light when white is legible enough.
+    /// let silver = SysColor::from_rgb(200, 200, 200);
+    /// let button = SysColor::from_rgb(150, 150, 150);
+    ///
+    /// let text = silver.with_min_contrast(button, 2.5);
+    /// assert!(text.contrast_ratio(button) >= 2.5);
+    /// assert_eq!(text.polarity(button), Polarity::LightOnDark);
+    /// ```
+    pub fn with_min_contrast(self, background: SysColor, min_ratio: f32) -> SysColor {
+        if self.contrast_ratio(background) >= min_ratio {
+            return self;
+        }
+
+        let (own, other) = match self.polarity(background) {
+            Polarity::LightOnDark => (WHITE, BLACK),
+            Polarity::DarkOnLight => (BLACK, WHITE),
+        };
+
+        let target = if own.contrast_ratio(background) >= min_ratio {
+            own
+        } else if other.contrast_ratio(background) >= min_ratio {
+            other
+        } else if own.contrast_ratio(background) >= other.contrast_ratio(background) {
+            return own;
+        } else {
+            return other;
+        };
+
+        // Binary search for the smallest step towards the target that is legible enough.
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..MAX_NUDGE_STEPS {
+            let mid = (low + high) / 2.0;
+            if self
+                .mix(target, mid, ColorSpace::Oklab)
+                .contrast_ratio(background)
+                >= min_ratio
+            {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        self.mix(target, high, ColorSpace::Oklab)
+    }
+
+    /// Get the polarity of this color as text on a background.
+    pub fn polarity(self, background: SysColor) -> Polarity {
+        if apca_luminance(self) > apca_luminance(background) {
+            Polarity::LightOnDark
+        } else {
+            Polarity::DarkOnLight
+        }
+    }
+}
+
+/// The number of bisection steps used by `SysColor::with_min_contrast`.
+const MAX_NUDGE_STEPS: usize = 16;
+
+/// Whether text is darker or lighter than its background.
+///
+/// APCA scores the two cases differently, since light text on a dark background needs more
+/// contrast to read as well as the reverse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Polarity {
+    /// Dark text on a light background, which has a positive Lc.
+    DarkOnLight,
+
+    /// Light text on a dark background, which has a negative Lc.
+    LightOnDark,
+}
+
+/// Luminance below which APCA softly clamps to account for flare.
+const APCA_BLACK_THRESHOLD: f32 = 0.022;
+
+/// Exponent of the soft clamp near black.
+const APCA_BLACK_CLAMP: f32 = 1.414;
+
+/// Luminance difference below which APCA reports no contrast.
+const APCA_DELTA_Y_MIN: f32 = 0.0005;
+
+/// Contrast below which APCA reports no contrast.
+const APCA_LO_CLIP: f32 = 0.1;
+
+/// Offset subtracted from the magnitude of the contrast.
+const APCA_OFFSET: f32 = 0.027;
+
+/// Get the screen luminance of a color as estimated by APCA.
+fn apca_luminance(color: SysColor) -> f32 {
+    let channel = |c: u8| libm::powf(f32::from(c) / 255.0, 2.4);
+
+    0.212_672_9 * channel(color.red())
+        + 0.715_152_2 * channel(color.green())
+        + 0.072_175 * channel(color.blue())
+}
+
+/// Softly clamp luminance near black.
+fn soft_clamp(y: f32) -> f32 {
+    if y > APCA_BLACK_THRESHOLD {
+        y
+    } else {
+        y + libm::powf(APCA_BLACK_THRESHOLD - y, APCA_BLACK_CLAMP)
+    }
+}
+
+/// A WCAG 2 conformance level for text contrast.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WcagLevel {
+    /// Level AA for normal text, requiring a ratio of 4.5:1.
+    Aa,
+
+    /// Level AA for large text, requiring a ratio of 3:1.
+    AaLarge,
+
+    /// Level AAA for normal text, requiring a ratio of 7:1.
+    Aaa,
+
+    /// Level AAA for large text, requiring a ratio of 4.5:1.
+    AaaLarge,
+}
+
+impl WcagLevel {
+    /// Get the minimum contrast ratio required by this level.
+    pub fn min_ratio(self) -> f32 {
+        match self {
+            WcagLevel::Aa | WcagLevel::AaaLarge => 4.5,
+            WcagLevel::AaLarge => 3.0,
+            WcagLevel::Aaa => 7.0,
+        }
+    }
+}
+
+/// A foreground color that Windows draws on top of a background color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContrastPair {
+    /// The color of the text or glyphs.
+    pub foreground: SysColorIndex,
+
+    /// The color they are drawn on.
+    pub background: SysColorIndex,
+}
+
+/// Shorthand for a pair in `ContrastPair::ALL`.
+const fn pair(foreground: SysColorIndex, background: SysColorIndex) -> ContrastPair {
+    ContrastPair {
+        foreground,
+        background,
+    }
+}
+
+impl ContrastPair {
+    /// Every pair of system colors that is expected to be legible together.
+    pub const ALL: &'static [ContrastPair] = &[
+        pair(SysColorIndex::WindowText, SysColorIndex::Window),
+        pair(SysColorIndex::HotLight, SysColorIndex::Window),
+        pair(SysColorIndex::GrayText, SysColorIndex::Window),
+        pair(SysColorIndex::HighlightText, SysColorIndex::Highlight),
+        pair(SysColorIndex::ButtonText, SysColorIndex::ButtonFace),
+        pair(SysColorIndex::GrayText, SysColorIndex::ButtonFace),
+        pair(SysColorIndex::InfoText, SysColorIndex::InfoBackground),
+        pair(SysColorIndex::MenuText, SysColorIndex::Menu),
+        pair(SysColorIndex::MenuText, SysColorIndex::MenuBar),
+        pair(SysColorIndex::HighlightText, SysColorIndex::MenuHighlight),
+        pair(SysColorIndex::CaptionText, SysColorIndex::ActiveCaption),
+        pair(
+            SysColorIndex::InactiveCaptionText,
+            SysColorIndex::InactiveCaption,
+        ),
+    ];
+
+    /// Get the contrast ratio of this pair in a palette.
+    ///
+    /// Returns `None` if either color is not present.
+    pub fn contrast_ratio(self, palette: &SysColorPalette) -> Option<f32> {
+        let foreground = palette.get(self.foreground)?;
+        let background = palette.get(self.background)?;
+        Some(foreground.contrast_ratio(background))
+    }
+
+    /// Get the APCA lightness contrast (Lc) of this pair in a palette.
+    ///
+    /// Returns `None` if either color is not present.
+    pub fn apca_contrast(self, palette: &SysColorPalette) -> Option<f32> {
+        let foreground = palette.get(self.foreground)?;
+        let background = palette.get(self.background)?;
+        Some(foreground.apca_contrast(background))
+    }
+}
+
+impl SysColorPalette {
+    /// Iterate over the contrast ratio of every pair in [`ContrastPair::ALL`].
+    ///
+    /// Pairs for which either color is not present are skipped.
+    pub fn contrast_ratios(&self) -> ContrastRatios<'_> {
+        ContrastRatios {
+            palette: self,
+            pairs: ContrastPair::ALL.iter(),
+        }
+    }
+
+    /// Iterate over the pairs in [`ContrastPair::ALL`] whose contrast ratio is below the minimum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorScheme, SysColorIndex, WcagLevel};
+    ///
+    /// let palette = ColorScheme::HighContrastBlack.palette();
+    /// assert_eq!(palette.contrast_failures(WcagLevel::Aa.min_ratio()).count(), 0);
+    ///
+    /// let mut palette = *ColorScheme::Windows10.palette();
+    /// palette.set(SysColorIndex::WindowText, palette.get(SysColorIndex::Window));
+    /// let failure = palette.contrast_failures(WcagLevel::Aa.min_ratio()).next().unwrap();
+    /// assert_eq!(failure.pair.foreground, SysColorIndex::WindowText);
+    /// ```
+    pub fn contrast_failures(&self, min_ratio: f32) -> ContrastFailures<'_> {
+        ContrastFailures {
+            inner: self.contrast_ratios(),
+            min_ratio,
+        }
+    }
+
+    /// Pick a readable text color for a background.
+    ///
+    /// The candidates are this palette's [`SysColorIndex::WindowText`] and
+    /// [`SysColorIndex::HighlightText`], if present, followed by black and white. The candidate
+    /// with the highest contrast ratio is chosen. If `min_ratio` is set and the chosen color does
+    /// not reach it, its lightness is adjusted with [`SysColor::with_min_contrast`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorScheme, SysColor, SysColorIndex};
+    ///
+    /// let palette = ColorScheme::Windows10.palette();
+    /// let tag = SysColor::from_rgb(250, 200, 60);
+    ///
+    /// let text = palette.readable_text(tag, Some(4.5));
+    /// assert_eq!(Some(text), palette.get(SysColorIndex::WindowText));
+    /// ```
+    pub fn readable_text(&self, background: SysColor, min_ratio: Option<f32>) -> SysColor {
+        let window_text = self.get(SysColorIndex::WindowText).unwrap_or(BLACK);
+        let highlight_text = self.get(SysColorIndex::HighlightText).unwrap_or(WHITE);
+
+        let text = background
+            .readable_text(&[window_text, highlight_text, BLACK, WHITE])
+            .unwrap_or(BLACK);
+
+        match min_ratio {
+            Some(min_ratio) => text.with_min_contrast(background, min_ratio),
+            None => text,
+        }
+    }
+
+    /// Iterate over the APCA lightness contrast (Lc) of every pair in [`ContrastPair::ALL`].
+    ///
+    /// Pairs for which either color is not present are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorScheme, Polarity, SysColorIndex};
+    ///
+    /// let palette = ColorScheme::HighContrastBlack.palette();
+    /// let (pair, lc) = palette.apca_contrasts().next().unwrap();
+    /// assert_eq!(pair.foreground, SysColorIndex::WindowText);
+    ///
+    /// let window_text = palette.get(SysColorIndex::WindowText).unwrap();
+    /// let window = palette.get(SysColorIndex::Window).unwrap();
+    /// assert_eq!(window_text.polarity(window), Polarity::LightOnDark);
+    /// assert!(lc < -100.0);
+    /// ```
+    pub fn apca_contrasts(&self) -> ApcaContrasts<'_> {
+        ApcaContrasts {
+            palette: self,
+            pairs: ContrastPair::ALL.iter(),
+        }
+    }
+}
+
+/// A pair of system colors whose contrast ratio is too low.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ContrastFailure {
+    /// The pair of colors.
+    pub pair: ContrastPair,
+
+    /// The contrast ratio between them.
+    pub ratio: f32,
+}
+
+/// An iterator over the contrast ratios of the pairs in a palette.
+///
+/// This is returned by [`SysColorPalette::contrast_ratios`].
+#[derive(Debug, Clone)]
+pub struct ContrastRatios<'a> {
+    palette: &'a SysColorPalette,
+    pairs: slice::Iter<'static, ContrastPair>,
+}
+
+impl Iterator for ContrastRatios<'_> {
+    type Item = (ContrastPair, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let palette = self.palette;
+        self.pairs
+            .by_ref()
+            .find_map(|&pair| pair.contrast_ratio(palette).map(|ratio| (pair, ratio)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pairs.size_hint().1)
+    }
+}
+
+impl FusedIterator for ContrastRatios<'_> {}
+
+/// An iterator over the pairs in a palette whose contrast ratio is too low.
+///
+/// This is returned by [`SysColorPalette::contrast_failures`].
+#[derive(Debug, Clone)]
+pub struct ContrastFailures<'a> {
+    inner: ContrastRatios<'a>,
+    min_ratio: f32,
+}
+
+impl Iterator for ContrastFailures<'_> {
+    type Item = ContrastFailure;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let min_ratio = self.min_ratio;
+        self.inner
+            .find(|&(_, ratio)| ratio < min_ratio)
+            .map(|(pair, ratio)| ContrastFailure { pair, ratio })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl FusedIterator for ContrastFailures<'_> {}
+
+/// An iterator over the APCA contrasts of the pairs in a palette.
+///
+/// This is returned by [`SysColorPalette::apca_contrasts`].
+#[derive(Debug, Clone)]
+pub struct ApcaContrasts<'a> {
+    palette: &'a SysColorPalette,
+    pairs: slice::Iter<'static, ContrastPair>,
+}
+
+impl Iterator for ApcaContrasts<'_> {
+    type Item = (ContrastPair, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let palette = self.palette;
+        self.pairs
+            .by_ref()
+            .find_map(|&pair| pair.apca_contrast(palette).map(|lc| (pair, lc)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pairs.size_hint().1)
+    }
+}
+
+impl FusedIterator for ApcaContrasts<'_> {}
@@ -0,0 +1,251 @@
+// Boost/Apache2 License
+
+//! Perceptual distances between colors.
+
+use crate::{ColorScheme, Lab, SysColor, SysColorIndex, SysColorPalette};
+
+/// A formula for the perceptual difference (delta E) between two colors.
+///
+/// Every formula works on [`Lab`] coordinates. A difference of about `1.0` is the smallest that
+/// most observers notice side by side.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DeltaE {
+    /// The Euclidean distance in CIELAB, from CIE 1976.
+    Cie76,
+
+    /// The CIE 1994 formula, with the weights for graphic arts.
+    ///
+    /// This formula is not symmetric: the first color is taken as the reference.
+    Cie94,
+
+    /// The CIEDE2000 formula.
+    #[default]
+    Ciede2000,
+}
+
+impl Lab {
+    /// Get the perceptual difference between this color and another one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{DeltaE, Lab};
+    ///
+    /// // The first pair of the reference data published by Sharma, Wu and Dalal.
+    /// let a = Lab { l: 50.0, a: 2.6772, b: -79.7751 };
+    /// let b = Lab { l: 50.0, a: 0.0, b: -82.7485 };
+    ///
+    /// assert!((a.delta_e(b, DeltaE::Ciede2000) - 2.0425).abs() < 0.001);
+    /// ```
+    pub fn delta_e(self, other: Lab, formula: DeltaE) -> f32 {
+        match formula {
+            DeltaE::Cie76 => cie76(self, other),
+            DeltaE::Cie94 => cie94(self, other),
+            DeltaE::Ciede2000 => ciede2000(self, other),
+        }
+    }
+}
+
+impl SysColor {
+    /// Get the perceptual difference between this color and another one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{DeltaE, SysColor};
+    ///
+    /// let face = SysColor::from_rgb(212, 208, 200);
+    /// let near = SysColor::from_rgb(212, 208, 201);
+    /// let far = SysColor::from_rgb(10, 36, 106);
+    ///
+    /// assert!(face.delta_e(near, DeltaE::Ciede2000) < 1.0);
+    /// assert!(face.delta_e(far, DeltaE::Ciede2000) > 30.0);
+    /// ```
+    pub fn delta_e(self, other: SysColor, formula: DeltaE) -> f32 {
+        Lab::from(self).delta_e(Lab::from(other), formula)
+    }
+}
+
+impl SysColorPalette {
+    /// Find the color in this palette that is perceptually closest to the given one.
+    ///
+    /// Returns the index of that color along with its difference, or `None` if no color is
+    /// present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorScheme, DeltaE, SysColor, SysColorIndex};
+    ///
+    /// let palette = ColorScheme::Windows10.palette();
+    /// let accent = SysColor::from_rgb(0, 118, 210);
+    ///
+    /// let (index, _) = palette.nearest(accent, DeltaE::Ciede2000).unwrap();
+    /// assert_eq!(index, SysColorIndex::Highlight);
+    /// ```
+    pub fn nearest(&self, color: SysColor, formula: DeltaE) -> Option<(SysColorIndex, f32)> {
+        let target = Lab::from(color);
+
+        self.iter()
+            .filter_map(|(index, entry)| {
+                entry.map(|entry| (index, target.delta_e(Lab::from(entry), formula)))
+            })
+            .fold(None, |best, (index, difference)| match best {
+                Some((_, best_difference)) if best_difference <= difference => best,
+                _ => Some((index, difference)),
+            })
+    }
+
+    /// Get the mean perceptual difference between the colors of this palette and another one.
+    ///
+    /// Only colors present in both palettes are compared. Returns `None` if there are none.
+    pub fn distance(&self, other: &SysColorPalette, formula: DeltaE) -> Option<f32> {
+        let (total, count) = self
+            .iter()
+            .filter_map(|(index, color)| Some((color?, other.get(index)?)))
+            .fold((0.0, 0u32), |(total, count), (a, b)| {
+                (total + a.delta_e(b, formula), count + 1)
+            });
+
+        if count == 0 {
+            None
+        } else {
+            Some(total / count as f32)
+        }
+    }
+}
+
+impl ColorScheme {
+    /// Find the built-in scheme whose palette is closest to the given one.
+    ///
+    /// Returns the scheme along with the mean difference as computed by
+    /// [`SysColorPalette::distance`], or `None` if the palette has no colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorScheme, DeltaE, SysColor, SysColorIndex};
+    ///
+    /// let mut palette = *ColorScheme::Desert.palette();
+    /// palette.set(SysColorIndex::Window, Some(SysColor::from_rgb(250, 250, 250)));
+    ///
+    /// let (scheme, _) = ColorScheme::nearest(&palette, DeltaE::Ciede2000).unwrap();
+    /// assert_eq!(scheme, ColorScheme::Desert);
+    /// ```
+    pub fn nearest(palette: &SysColorPalette, formula: DeltaE) -> Option<(ColorScheme, f32)> {
+        ColorScheme::ALL
+            .iter()
+            .filter_map(|&scheme| {
+                scheme
+                    .palette()
+                    .distance(palette, formula)
+                    .map(|distance| (scheme, distance))
+            })
+            .fold(None, |best, (scheme, distance)| match best {
+                Some((_, best_distance)) if best_distance <= distance => best,
+                _ => Some((scheme, distance)),
+            })
+    }
+}
+
+/// The CIE 1976 color difference.
+fn cie76(x: Lab, y: Lab) -> f32 {
+    let (dl, da, db) = (x.l - y.l, x.a - y.a, x.b - y.b);
+    libm::sqrtf(dl * dl + da * da + db * db)
+}
+
+/// The CIE 1994 color difference, with the graphic arts weights.
+fn cie94(x: Lab, y: Lab) -> f32 {
+    const K1: f32 = 0.045;
+    const K2: f32 = 0.015;
+
+    let c1 = libm::sqrtf(x.a * x.a + x.b * x.b);
+    let c2 = libm::sqrtf(y.a * y.a + y.b * y.b);
+
+    let dl = x.l - y.l;
+    let dc = c1 - c2;
+    let (da, db) = (x.a - y.a, x.b - y.b);
+    let dh_squared = (da * da + db * db - dc * dc).max(0.0);
+
+    let sc = 1.0 + K1 * c1;
+    let sh = 1.0 + K2 * c1;
+
+    libm::sqrtf(dl * dl + (dc / sc) * (dc / sc) + dh_squared / (sh * sh))
+}
+
+/// The CIEDE2000 color difference.
+fn ciede2000(x: Lab, y: Lab) -> f32 {
+    // 25 to the seventh power.
+    const POW25_7: f32 = 6_103_515_625.0;
+
+    let pow7 = |v: f32| libm::powf(v, 7.0);
+    let hue = |b: f32, a: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            let h = libm::atan2f(b, a).to_degrees();
+            if h < 0.0 {
+                h + 360.0
+            } else {
+                h
+            }
+        }
+    };
+    let cos = |degrees: f32| libm::cosf(degrees.to_radians());
+    let sin = |degrees: f32| libm::sinf(degrees.to_radians());
+
+    // Adjust the a axis for the neutral colors.
+    let c1 = libm::sqrtf(x.a * x.a + x.b * x.b);
+    let c2 = libm::sqrtf(y.a * y.a + y.b * y.b);
+    let c_bar = (c1 + c2) / 2.0;
+    let g = 0.5 * (1.0 - libm::sqrtf(pow7(c_bar) / (pow7(c_bar) + POW25_7)));
+
+    let a1 = (1.0 + g) * x.a;
+    let a2 = (1.0 + g) * y.a;
+    let c1 = libm::sqrtf(a1 * a1 + x.b * x.b);
+    let c2 = libm::sqrtf(a2 * a2 + y.b * y.b);
+    let h1 = hue(x.b, a1);
+    let h2 = hue(y.b, a2);
+
+    // Compute the differences in lightness, chroma and hue.
+    let dl = y.l - x.l;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh = 2.0 * libm::sqrtf(c1 * c2) * sin(dh / 2.0);
+
+    // Compute the weighting functions.
+    let l_bar = (x.l + y.l) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t =
+        1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar) + 0.32 * cos(3.0 * h_bar + 6.0)
+            - 0.20 * cos(4.0 * h_bar - 63.0);
+    let d_theta = 30.0 * libm::expf(-((h_bar - 275.0) / 25.0) * ((h_bar - 275.0) / 25.0));
+    let rc = 2.0 * libm::sqrtf(pow7(c_bar) / (pow7(c_bar) + POW25_7));
+    let l_offset = (l_bar - 50.0) * (l_bar - 50.0);
+    let sl = 1.0 + 0.015 * l_offset / libm::sqrtf(20.0 + l_offset);
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -sin(2.0 * d_theta) * rc;
+
+    let (l, c, h) = (dl / sl, dc / sc, dh / sh);
+    libm::sqrtf((l * l + c * c + h * h + rt * c * h).max(0.0))
+}
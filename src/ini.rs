@@ -0,0 +1,87 @@
+// Boost/Apache2 License
+
+//! A minimal reader for INI-style files.
+
+/// A single line of an INI-style file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Line<'a> {
+    /// An empty line or a comment.
+    Blank,
+
+    /// A `[Section]` header, without the brackets.
+    Section(&'a str),
+
+    /// A `key=value` entry, with surrounding whitespace removed.
+    Entry { key: &'a str, value: &'a str },
+
+    /// A header missing its closing bracket.
+    UnclosedSection,
+
+    /// A line that is neither a header nor an entry.
+    MissingSeparator,
+}
+
+/// An iterator over the lines of an INI-style file.
+///
+/// Yields the one-based line number, the raw line without its terminator and the parsed line.
+pub(crate) struct Lines<'a> {
+    lines: core::str::SplitTerminator<'a, char>,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    /// Create an iterator over the lines of the text, skipping a leading byte order mark.
+    pub(crate) fn new(text: &'a str) -> Self {
+        Lines {
+            lines: text
+                .strip_prefix('\u{feff}')
+                .unwrap_or(text)
+                .split_terminator('\n'),
+            number: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = (usize, &'a str, Line<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let raw = self.lines.next()?;
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        self.number += 1;
+
+        Some((self.number, raw, parse_line(raw)))
+    }
+}
+
+/// Tell whether a section header names the given section.
+pub(crate) fn is_section(header: &str, name: &str) -> bool {
+    header.trim().eq_ignore_ascii_case(name)
+}
+
+/// Tell whether the text ends with the suffix, ignoring ASCII case.
+pub(crate) fn ends_with_ignore_case(text: &str, suffix: &str) -> bool {
+    let split = text.len().wrapping_sub(suffix.len());
+    matches!(text.get(split..), Some(end) if end.eq_ignore_ascii_case(suffix))
+}
+
+/// Parse a single line.
+fn parse_line(raw: &str) -> Line<'_> {
+    let line = raw.trim();
+
+    if line.is_empty() || line.starts_with(';') {
+        Line::Blank
+    } else if let Some(header) = line.strip_prefix('[') {
+        match header.strip_suffix(']') {
+            Some(name) => Line::Section(name),
+            None => Line::UnclosedSection,
+        }
+    } else if let Some(equals) = line.find('=') {
+        Line::Entry {
+            key: line[..equals].trim_end(),
+            value: line[equals + 1..].trim_start(),
+        }
+    } else {
+        Line::MissingSeparator
+    }
+}
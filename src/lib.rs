@@ -1,6 +1,5 @@
 // Boost/Apache2 License
 
-#![cfg(windows)]
 #![deny(unsafe_code)]
 #![forbid(future_incompatible, missing_docs, rust_2018_idioms)]
 #![no_std]
@@ -10,18 +9,103 @@
 //! This crate provides a safe wrapper around the `GetSysColor` function. To get a color, call
 //! [`SysColor::get`]. The available colors are listed in the [`SysColorIndex`] enum.
 //!
+//! The [`SysColor`] type and the [`SysColorIndex`] enum are available on every platform, so
+//! cross-platform code can name them freely. Lookups work on every platform too, but outside of
+//! Windows they only return colors once a provider has been installed.
+//!
+//! Colors are looked up through a [`SysColorProvider`]. On Windows the default provider is
+//! `Win32Provider`, which calls `GetSysColor`. Another provider can be installed with
+//! [`set_provider`], for instance to feed recorded colors into tests or to supply colors on
+//! other platforms. [`MockProvider`] is an in-memory provider suited to deterministic tests.
+//!
+//! To read every color, take a [`SysColorPalette`] snapshot. Two snapshots can be
+//! compared with [`SysColorPalette::diff`] to find out which colors changed. Reference palettes
+//! for well-known Windows color schemes are available through [`ColorScheme`].
+//!
+//! [`SysColor`] converts to and from other color spaces, such as [`Hsl`], [`Lab`] and [`Oklab`],
+//! through the `From` trait. Colors can be mixed, lightened, darkened and composited in a
+//! selectable [`ColorSpace`]. Translucent colors, such as the DWM colorization color, are
+//! represented by [`Rgba`] and [`PremultipliedRgba`].
+//!
+//! To check that text stays legible, [`SysColorPalette::contrast_failures`] reports every
+//! [`ContrastPair`] whose WCAG 2 contrast ratio is below a threshold, and
+//! [`SysColorPalette::apca_contrasts`] scores the same pairs with APCA.
+//! [`SysColorPalette::readable_text`] picks a legible text color for any background.
+//! [`SysColorPalette::simulate`] previews a palette under a [`ColorDeficiency`].
+//!
+//! Perceptual differences between colors are measured with [`SysColor::delta_e`], which also
+//! powers [`SysColorPalette::nearest`] and [`ColorScheme::nearest`].
+//!
+//! Windows `.theme` files are read with [`Theme::parse`] and written with
+//! [`SysColorPalette::write_theme`] or [`SysColorPalette::update_theme`], on every platform.
+//! Registry exports of `HKCU\Control Panel\Colors` are read with [`SysColorPalette::from_reg`]
+//! and written with [`SysColorPalette::write_reg`]. With the `themepack` feature, the theme
+//! inside a `.themepack` or `.deskthemepack` archive is read with `ThemePack::read`. The
+//! binary schemes of the classic Appearance dialog are handled by [`AppearanceScheme`].
+//!
 //! # Examples
 //!
 //! ```
+//! # #[cfg(windows)] {
 //! use win_syscolor::{SysColor, SysColorIndex};
 //!
 //! let color = SysColor::get(SysColorIndex::ActiveCaption).expect("Color not available");
 //! println!("The active caption color is {}", color);
+//! # }
 //! ```
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::convert::TryFrom;
 use core::fmt;
+use core::str::FromStr;
 use core::sync::atomic::{AtomicU8, Ordering};
-use windows_sys::Win32::Graphics::Gdi;
+
+mod adjust;
+mod alpha;
+mod appearance;
+mod contrast;
+mod distance;
+mod ini;
+mod mock;
+mod palette;
+mod parse;
+mod provider;
+mod reg;
+mod scheme;
+mod space;
+mod theme;
+#[cfg(feature = "themepack")]
+mod themepack;
+mod vision;
+
+#[cfg(feature = "serde")]
+pub mod serde_format;
+
+pub use adjust::ColorSpace;
+pub use alpha::{PremultipliedRgba, Rgba};
+pub use appearance::{
+    AppearanceError, AppearanceErrorKind, AppearanceScheme, FaceName, LogFont, NonClientMetrics,
+};
+pub use contrast::{
+    ApcaContrasts, ContrastFailure, ContrastFailures, ContrastPair, ContrastRatios, Polarity,
+    WcagLevel,
+};
+pub use distance::DeltaE;
+pub use mock::MockProvider;
+pub use palette::{PaletteChange, PaletteDiff, PaletteIter, SysColorPalette};
+pub use parse::{ParseColorError, ParseColorErrorKind};
+#[cfg(windows)]
+pub use provider::Win32Provider;
+pub use provider::{reset_provider, set_provider, SysColorProvider};
+pub use reg::{RegError, RegErrorKind, RegFormat};
+pub use scheme::ColorScheme;
+pub use space::{Hsl, Hsv, Lab, LinearRgb, Oklab, Oklch, Xyz};
+pub use theme::{Desktop, Theme, ThemeError, ThemeErrorKind, VisualStyles};
+#[cfg(feature = "themepack")]
+pub use themepack::{Assets, ThemePack, ThemePackError};
+pub use vision::{ColorDeficiency, IndistinguishablePairs};
 
 /// The system color.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -50,10 +134,54 @@ impl fmt::Display for SysColor {
 }
 
 impl SysColor {
-    fn new(color: u32) -> Self {
+    const fn new(color: u32) -> Self {
         SysColor(color)
     }
 
+    /// Create a color from its red, green and blue components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// let color = SysColor::from_rgb(0x12, 0x34, 0x56);
+    /// assert_eq!(color.to_string(), "#123456");
+    /// assert_eq!(color.raw(), 0x0056_3412);
+    /// ```
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        SysColor::new(red as u32 | (green as u32) << 8 | (blue as u32) << 16)
+    }
+
+    /// Create a color from a raw `COLORREF` value in `0x00BBGGRR` form.
+    ///
+    /// Returns `None` if the reserved high byte is not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColor;
+    ///
+    /// assert_eq!(SysColor::from_colorref(0x0056_3412), Some(SysColor::from_rgb(0x12, 0x34, 0x56)));
+    /// assert_eq!(SysColor::from_colorref(0x0100_0000), None);
+    /// ```
+    pub const fn from_colorref(raw: u32) -> Option<Self> {
+        if raw >> 24 == 0 {
+            Some(SysColor::new(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Get the system color.
+    ///
+    /// The color is looked up through the provider installed with [`set_provider`]. If no
+    /// provider has been installed, `Win32Provider` is used on Windows and `None` is returned
+    /// on other platforms.
+    pub fn get(index: SysColorIndex) -> Option<Self> {
+        provider::get(index)
+    }
+
     /// Get the raw color.
     pub fn raw(self) -> u32 {
         self.0
@@ -93,74 +221,354 @@ impl From<SysColor> for (u8, u8, u8) {
     }
 }
 
-/// Generate the `SysColor` struct and associated functions.
+impl From<[u8; 3]> for SysColor {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        SysColor::from_rgb(red, green, blue)
+    }
+}
+
+impl From<(u8, u8, u8)> for SysColor {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        SysColor::from_rgb(red, green, blue)
+    }
+}
+
+/// Generate the `SysColorIndex` enum and its metadata.
 macro_rules! generate_syscolor {
-    ($($wname:ident => $name:ident),*) => {
+    ($($wname:ident = $raw:literal => $name:ident / $reg:literal: $desc:literal),*) => {
         /// The available system colors.
         #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
         #[non_exhaustive]
         pub enum SysColorIndex {
             $(
-                #[doc = concat!("The `", stringify!($name), "` color.")]
+                #[doc = $desc]
                 $name,
             )*
         }
 
-        impl SysColor {
-            /// Get the system color.
-            pub fn get(index: SysColorIndex) -> Option<Self> {
-                match index {
+        impl SysColorIndex {
+            /// Every available system color, in order.
+            pub const ALL: &'static [SysColorIndex] = &[$(SysColorIndex::$name),*];
+
+            /// The number of available system colors.
+            const COUNT: usize = [$(SysColorIndex::$name),*].len();
+
+            /// Get the raw Win32 `COLOR_*` index for this color.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use win_syscolor::SysColorIndex;
+            ///
+            /// assert_eq!(SysColorIndex::Window.raw(), 5);
+            /// ```
+            pub const fn raw(self) -> i32 {
+                match self {
+                    $(
+                        SysColorIndex::$name => $raw,
+                    )*
+                }
+            }
+
+            /// Get the name of the Win32 `COLOR_*` constant for this color.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use win_syscolor::SysColorIndex;
+            ///
+            /// assert_eq!(SysColorIndex::ButtonFace.win32_name(), "COLOR_BTNFACE");
+            /// ```
+            pub const fn win32_name(self) -> &'static str {
+                match self {
+                    $(
+                        SysColorIndex::$name => stringify!($wname),
+                    )*
+                }
+            }
+
+            /// Get the name of this variant.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use win_syscolor::SysColorIndex;
+            ///
+            /// assert_eq!(SysColorIndex::ButtonFace.name(), "ButtonFace");
+            /// ```
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(
+                        SysColorIndex::$name => stringify!($name),
+                    )*
+                }
+            }
+
+            /// Get the name of the value for this color under the `HKCU\Control Panel\Colors`
+            /// registry key.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use win_syscolor::SysColorIndex;
+            ///
+            /// assert_eq!(SysColorIndex::Highlight.registry_name(), "Hilight");
+            /// ```
+            pub const fn registry_name(self) -> &'static str {
+                match self {
                     $(
-                        SysColorIndex::$name => {
-                            // Cache whether or not the value is present.
-                            static PRESENT: OnceBool = OnceBool::new();
+                        SysColorIndex::$name => $reg,
+                    )*
+                }
+            }
 
-                            get_sys_color(Gdi::$wname, &PRESENT).map(SysColor::new)
-                        }
+            /// Get a human-readable description of where this color is used.
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(
+                        SysColorIndex::$name => $desc,
                     )*
                 }
             }
         }
+
+        // Check the indices above against the constants in `windows-sys`.
+        #[cfg(windows)]
+        const _: () = {
+            use windows_sys::Win32::Graphics::Gdi;
+
+            $(
+                assert!($raw == Gdi::$wname, stringify!($wname));
+            )*
+        };
     }
 }
 
 generate_syscolor! {
-    COLOR_3DDKSHADOW => ThreeDDarkShadow,
-    COLOR_ACTIVEBORDER => ActiveBorder,
-    COLOR_ACTIVECAPTION => ActiveCaption,
-    COLOR_APPWORKSPACE => AppWorkspace,
-    COLOR_BACKGROUND => Background,
-    COLOR_BTNFACE => ButtonFace,
-    COLOR_BTNHIGHLIGHT => ButtonHighlight,
-    COLOR_BTNSHADOW => ButtonShadow,
-    COLOR_BTNTEXT => ButtonText,
-    COLOR_CAPTIONTEXT => CaptionText,
-    COLOR_GRADIENTACTIVECAPTION => GradientActiveCaption,
-    COLOR_GRADIENTINACTIVECAPTION => GradientInactiveCaption,
-    COLOR_GRAYTEXT => GrayText,
-    COLOR_HIGHLIGHT => Highlight,
-    COLOR_HIGHLIGHTTEXT => HighlightText,
-    COLOR_HOTLIGHT => HotLight,
-    COLOR_INACTIVEBORDER => InactiveBorder,
-    COLOR_INACTIVECAPTION => InactiveCaption,
-    COLOR_INACTIVECAPTIONTEXT => InactiveCaptionText,
-    COLOR_INFOBK => InfoBackground,
-    COLOR_INFOTEXT => InfoText,
-    COLOR_MENU => Menu,
-    COLOR_MENUTEXT => MenuText,
-    COLOR_SCROLLBAR => ScrollBar,
-    COLOR_WINDOW => Window,
-    COLOR_WINDOWFRAME => WindowFrame,
-    COLOR_WINDOWTEXT => WindowText
+    COLOR_3DDKSHADOW = 21 => ThreeDDarkShadow / "ButtonDkShadow": "Dark shadow for three-dimensional display elements.",
+    COLOR_3DLIGHT = 22 => ThreeDLight / "ButtonLight": "Light color for three-dimensional display elements, on edges facing the light source.",
+    COLOR_ACTIVEBORDER = 10 => ActiveBorder / "ActiveBorder": "Active window border.",
+    COLOR_ACTIVECAPTION = 2 => ActiveCaption / "ActiveTitle": "Active window title bar.",
+    COLOR_APPWORKSPACE = 12 => AppWorkspace / "AppWorkspace": "Background color of multiple document interface (MDI) applications.",
+    COLOR_BACKGROUND = 1 => Background / "Background": "Desktop background.",
+    COLOR_BTNFACE = 15 => ButtonFace / "ButtonFace": "Face color for three-dimensional display elements and for dialog box backgrounds.",
+    COLOR_BTNHIGHLIGHT = 20 => ButtonHighlight / "ButtonHilight": "Highlight color for three-dimensional display elements, on edges facing the light source.",
+    COLOR_BTNSHADOW = 16 => ButtonShadow / "ButtonShadow": "Shadow color for three-dimensional display elements, on edges facing away from the light source.",
+    COLOR_BTNTEXT = 18 => ButtonText / "ButtonText": "Text on push buttons.",
+    COLOR_CAPTIONTEXT = 9 => CaptionText / "TitleText": "Text in captions, size boxes and scroll bar arrow boxes.",
+    COLOR_GRADIENTACTIVECAPTION = 27 => GradientActiveCaption / "GradientActiveTitle": "Right side color in the gradient of an active window's title bar.",
+    COLOR_GRADIENTINACTIVECAPTION = 28 => GradientInactiveCaption / "GradientInactiveTitle": "Right side color in the gradient of an inactive window's title bar.",
+    COLOR_GRAYTEXT = 17 => GrayText / "GrayText": "Grayed (disabled) text.",
+    COLOR_HIGHLIGHT = 13 => Highlight / "Hilight": "Items selected in a control.",
+    COLOR_HIGHLIGHTTEXT = 14 => HighlightText / "HilightText": "Text of items selected in a control.",
+    COLOR_HOTLIGHT = 26 => HotLight / "HotTrackingColor": "Hyperlinks and hot-tracked items.",
+    COLOR_INACTIVEBORDER = 11 => InactiveBorder / "InactiveBorder": "Inactive window border.",
+    COLOR_INACTIVECAPTION = 3 => InactiveCaption / "InactiveTitle": "Inactive window title bar.",
+    COLOR_INACTIVECAPTIONTEXT = 19 => InactiveCaptionText / "InactiveTitleText": "Text in an inactive window title bar.",
+    COLOR_INFOBK = 24 => InfoBackground / "InfoWindow": "Background color for tooltip controls.",
+    COLOR_INFOTEXT = 23 => InfoText / "InfoText": "Text color for tooltip controls.",
+    COLOR_MENU = 4 => Menu / "Menu": "Menu background.",
+    COLOR_MENUBAR = 30 => MenuBar / "MenuBar": "Menu bar background when menus appear as flat menus.",
+    COLOR_MENUHILIGHT = 29 => MenuHighlight / "MenuHilight": "Highlighted menu items when menus appear as flat menus.",
+    COLOR_MENUTEXT = 7 => MenuText / "MenuText": "Text in menus.",
+    COLOR_SCROLLBAR = 0 => ScrollBar / "Scrollbar": "Scroll bar gray area.",
+    COLOR_WINDOW = 5 => Window / "Window": "Window background.",
+    COLOR_WINDOWFRAME = 6 => WindowFrame / "WindowFrame": "Window frame.",
+    COLOR_WINDOWTEXT = 8 => WindowText / "WindowText": "Text in windows."
 }
 
+/// Aliases for system colors, as `(win32_name, name, index)`.
+///
+/// These mirror the Win32 constants that share their value with another constant.
+const ALIASES: &[(&str, &str, SysColorIndex)] = &[
+    ("COLOR_DESKTOP", "Desktop", SysColorIndex::Background),
+    ("COLOR_3DFACE", "ThreeDFace", SysColorIndex::ButtonFace),
+    (
+        "COLOR_3DSHADOW",
+        "ThreeDShadow",
+        SysColorIndex::ButtonShadow,
+    ),
+    (
+        "COLOR_3DHIGHLIGHT",
+        "ThreeDHighlight",
+        SysColorIndex::ButtonHighlight,
+    ),
+    (
+        "COLOR_3DHILIGHT",
+        "ThreeDHilight",
+        SysColorIndex::ButtonHighlight,
+    ),
+    (
+        "COLOR_BTNHILIGHT",
+        "ButtonHilight",
+        SysColorIndex::ButtonHighlight,
+    ),
+];
+
+#[allow(non_upper_case_globals)]
+impl SysColorIndex {
+    /// Alias for [`SysColorIndex::Background`], matching `COLOR_DESKTOP`.
+    pub const Desktop: SysColorIndex = SysColorIndex::Background;
+
+    /// Alias for [`SysColorIndex::ButtonFace`], matching `COLOR_3DFACE`.
+    pub const ThreeDFace: SysColorIndex = SysColorIndex::ButtonFace;
+
+    /// Alias for [`SysColorIndex::ButtonShadow`], matching `COLOR_3DSHADOW`.
+    pub const ThreeDShadow: SysColorIndex = SysColorIndex::ButtonShadow;
+
+    /// Alias for [`SysColorIndex::ButtonHighlight`], matching `COLOR_3DHIGHLIGHT`.
+    pub const ThreeDHighlight: SysColorIndex = SysColorIndex::ButtonHighlight;
+
+    /// Alias for [`SysColorIndex::ButtonHighlight`], matching `COLOR_3DHILIGHT`.
+    pub const ThreeDHilight: SysColorIndex = SysColorIndex::ButtonHighlight;
+
+    /// Alias for [`SysColorIndex::ButtonHighlight`], matching `COLOR_BTNHILIGHT`.
+    pub const ButtonHilight: SysColorIndex = SysColorIndex::ButtonHighlight;
+}
+
+impl SysColorIndex {
+    /// Iterate over every available system color, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColorIndex;
+    ///
+    /// for index in SysColorIndex::iter() {
+    ///     println!("{} ({}): {}", index.name(), index.win32_name(), index.description());
+    /// }
+    /// ```
+    pub fn iter() -> core::iter::Copied<core::slice::Iter<'static, SysColorIndex>> {
+        SysColorIndex::ALL.iter().copied()
+    }
+
+    /// Get the color for a raw Win32 `COLOR_*` index.
+    ///
+    /// Returns `None` if the index does not correspond to an available color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColorIndex;
+    ///
+    /// assert_eq!(SysColorIndex::from_raw(5), Some(SysColorIndex::Window));
+    /// assert_eq!(SysColorIndex::from_raw(-1), None);
+    /// ```
+    pub fn from_raw(raw: i32) -> Option<Self> {
+        SysColorIndex::iter().find(|index| index.raw() == raw)
+    }
+
+    /// Get the color for a name.
+    ///
+    /// The name may be the name of the variant (`"ButtonFace"`), the name of the Win32 constant
+    /// with or without its prefix (`"COLOR_BTNFACE"` or `"BtnFace"`), or the name of the registry
+    /// value (`"ButtonFace"`, `"Hilight"`). Aliases such as `"COLOR_3DFACE"` resolve to the
+    /// canonical color. Names are compared without regard to ASCII case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::SysColorIndex;
+    ///
+    /// assert_eq!(SysColorIndex::from_name("COLOR_BTNFACE"), Some(SysColorIndex::ButtonFace));
+    /// assert_eq!(SysColorIndex::from_name("BtnFace"), Some(SysColorIndex::ButtonFace));
+    /// assert_eq!(SysColorIndex::from_name("Hilight"), Some(SysColorIndex::Highlight));
+    /// assert_eq!(SysColorIndex::from_name("COLOR_3DFACE"), Some(SysColorIndex::ButtonFace));
+    /// assert_eq!(SysColorIndex::from_name("Chartreuse"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        // The Win32 constant may be written without its prefix.
+        let unprefixed = match name.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("COLOR_") => &name[6..],
+            _ => name,
+        };
+
+        SysColorIndex::iter()
+            .find(|index| {
+                index.name().eq_ignore_ascii_case(name)
+                    || index.win32_name()[6..].eq_ignore_ascii_case(unprefixed)
+                    || index.registry_name().eq_ignore_ascii_case(name)
+            })
+            .or_else(|| {
+                ALIASES.iter().find_map(|&(win32_name, alias, index)| {
+                    if alias.eq_ignore_ascii_case(name)
+                        || win32_name[6..].eq_ignore_ascii_case(unprefixed)
+                    {
+                        Some(index)
+                    } else {
+                        None
+                    }
+                })
+            })
+    }
+}
+
+impl From<SysColorIndex> for i32 {
+    fn from(index: SysColorIndex) -> Self {
+        index.raw()
+    }
+}
+
+impl TryFrom<i32> for SysColorIndex {
+    type Error = InvalidSysColorIndex;
+
+    fn try_from(raw: i32) -> Result<Self, Self::Error> {
+        SysColorIndex::from_raw(raw).ok_or(InvalidSysColorIndex(raw))
+    }
+}
+
+impl FromStr for SysColorIndex {
+    type Err = ParseSysColorIndexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SysColorIndex::from_name(s).ok_or(ParseSysColorIndexError { _private: () })
+    }
+}
+
+/// The error returned when converting an invalid raw index into a [`SysColorIndex`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InvalidSysColorIndex(i32);
+
+impl InvalidSysColorIndex {
+    /// Get the raw index that was rejected.
+    pub fn raw(self) -> i32 {
+        self.0
+    }
+}
+
+impl fmt::Display for InvalidSysColorIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid system color index: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidSysColorIndex {}
+
+/// The error returned when parsing an unknown name into a [`SysColorIndex`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ParseSysColorIndexError {
+    _private: (),
+}
+
+impl fmt::Display for ParseSysColorIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown system color name")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSysColorIndexError {}
+
 /// A lazily-initialized boolean value.
+#[cfg_attr(not(windows), allow(dead_code))]
 struct OnceBool(AtomicU8);
 
 const UNINIT: u8 = 0xFF;
 const FALSE: u8 = 0;
 const TRUE: u8 = 1;
 
+#[cfg_attr(not(windows), allow(dead_code))]
 impl OnceBool {
     /// Creates a new `OnceBool` in an uninitialized state.
     const fn new() -> Self {
@@ -185,28 +593,48 @@ impl OnceBool {
             let new_value = (closure.take().unwrap())() as u8;
 
             // Try to set the value.
-            value = self
+            match self
                 .0
                 .compare_exchange(value, new_value, Ordering::AcqRel, Ordering::Acquire)
-                .unwrap_or_else(|x| x);
+            {
+                Ok(_) => return new_value == TRUE,
+                Err(x) => value = x,
+            }
         }
     }
 }
 
-#[allow(unsafe_code)]
-#[inline]
-fn get_sys_color(index: i32, present: &'static OnceBool) -> Option<u32> {
-    // See if the color is present.
-    let present = present.get_or_init(move || {
-        let brush = unsafe { Gdi::GetSysColorBrush(index) };
-        brush != 0
-    });
+#[cfg(test)]
+mod tests {
+    use super::OnceBool;
+    use core::cell::Cell;
+
+    #[test]
+    fn once_bool_initializes_once() {
+        let calls = Cell::new(0);
+        let once = OnceBool::new();
 
-    if !present {
-        return None;
+        let first = once.get_or_init(|| {
+            calls.set(calls.get() + 1);
+            true
+        });
+        assert!(first);
+        assert_eq!(calls.get(), 1);
+
+        // Later calls return the cached value without running the closure.
+        let second = once.get_or_init(|| {
+            calls.set(calls.get() + 1);
+            false
+        });
+        assert!(second);
+        assert_eq!(calls.get(), 1);
     }
 
-    // Get the color.
-    let color = unsafe { Gdi::GetSysColor(index) };
-    Some(color)
+    #[test]
+    fn once_bool_caches_false() {
+        let once = OnceBool::new();
+
+        assert!(!once.get_or_init(|| false));
+        assert!(!once.get_or_init(|| true));
+    }
 }
@@ -0,0 +1,126 @@
+// Boost/Apache2 License
+
+//! An in-memory provider for deterministic tests.
+
+use crate::{SysColor, SysColorIndex, SysColorProvider};
+
+use core::fmt;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// The marker for a color that is not present.
+///
+/// The high byte of a `COLORREF` is always zero, so this can never be a valid color.
+const NOT_PRESENT: u32 = u32::MAX;
+
+/// An in-memory table of system colors.
+///
+/// Every entry starts out as not present, mirroring a color for which `GetSysColorBrush` returns
+/// null. Entries can be set, changed and removed through a shared reference, so a `MockProvider`
+/// can live in a `static` and be installed with [`set_provider`](crate::set_provider).
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{set_provider, MockProvider, SysColor, SysColorIndex};
+///
+/// static MOCK: MockProvider = MockProvider::new();
+///
+/// MOCK.set_rgb(SysColorIndex::Window, 255, 255, 255);
+/// set_provider(&MOCK);
+///
+/// let window = SysColor::get(SysColorIndex::Window).unwrap();
+/// assert_eq!(window.to_string(), "#FFFFFF");
+/// assert_eq!(SysColor::get(SysColorIndex::MenuText), None);
+/// ```
+pub struct MockProvider {
+    /// The raw colors, or `NOT_PRESENT`.
+    colors: [AtomicU32; SysColorIndex::COUNT],
+}
+
+impl MockProvider {
+    /// Create a new `MockProvider` with no colors present.
+    pub const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: AtomicU32 = AtomicU32::new(NOT_PRESENT);
+
+        MockProvider {
+            colors: [EMPTY; SysColorIndex::COUNT],
+        }
+    }
+
+    /// Get the color at the given index, or `None` if it is not present.
+    pub fn get(&self, index: SysColorIndex) -> Option<SysColor> {
+        decode(self.colors[index as usize].load(Ordering::Acquire))
+    }
+
+    /// Tell whether the color at the given index is present.
+    pub fn is_present(&self, index: SysColorIndex) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Set the color at the given index, returning the previous value.
+    ///
+    /// Setting `None` marks the color as not present.
+    pub fn set(&self, index: SysColorIndex, color: Option<SysColor>) -> Option<SysColor> {
+        let raw = color.map_or(NOT_PRESENT, SysColor::raw);
+        decode(self.colors[index as usize].swap(raw, Ordering::AcqRel))
+    }
+
+    /// Set the color at the given index from its components, returning the previous value.
+    pub fn set_rgb(&self, index: SysColorIndex, red: u8, green: u8, blue: u8) -> Option<SysColor> {
+        self.set(index, Some(SysColor::from_rgb(red, green, blue)))
+    }
+
+    /// Mark the color at the given index as not present, returning the previous value.
+    pub fn remove(&self, index: SysColorIndex) -> Option<SysColor> {
+        self.set(index, None)
+    }
+
+    /// Mark every color as not present.
+    pub fn clear(&self) {
+        for color in &self.colors {
+            color.store(NOT_PRESENT, Ordering::Release);
+        }
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MockProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Entry(u32);
+
+        impl fmt::Debug for Entry {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&decode(self.0), f)
+            }
+        }
+
+        f.debug_list()
+            .entries(
+                self.colors
+                    .iter()
+                    .map(|color| Entry(color.load(Ordering::Acquire))),
+            )
+            .finish()
+    }
+}
+
+impl SysColorProvider for MockProvider {
+    fn sys_color(&self, index: SysColorIndex) -> Option<SysColor> {
+        self.get(index)
+    }
+}
+
+/// Convert a stored value back into a color.
+fn decode(raw: u32) -> Option<SysColor> {
+    if raw == NOT_PRESENT {
+        None
+    } else {
+        Some(SysColor::new(raw))
+    }
+}
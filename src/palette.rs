@@ -0,0 +1,320 @@
+// Boost/Apache2 License
+
+//! Snapshots of every system color.
+
+use crate::{provider, SysColor, SysColorIndex, SysColorProvider};
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::Index;
+use core::slice;
+
+/// A snapshot of every system color.
+///
+/// Each [`SysColorIndex`] maps to the color that was present when the snapshot was taken, or
+/// `None` if the color was not present. [`SysColorPalette::current`] reads every color from a
+/// single provider, which makes the palette a convenient unit to cache and compare.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{MockProvider, SysColorIndex, SysColorPalette};
+///
+/// let mock = MockProvider::new();
+/// mock.set_rgb(SysColorIndex::Window, 255, 255, 255);
+///
+/// let palette = SysColorPalette::from_provider(&mock);
+/// assert!(palette.is_present(SysColorIndex::Window));
+/// assert!(!palette.is_present(SysColorIndex::WindowText));
+/// assert_eq!(palette.iter().filter(|(_, color)| color.is_some()).count(), 1);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SysColorPalette {
+    /// The colors, indexed by `SysColorIndex`.
+    colors: [Option<SysColor>; SysColorIndex::COUNT],
+}
+
+impl SysColorPalette {
+    /// Create a new palette with no colors present.
+    pub const fn new() -> Self {
+        SysColorPalette {
+            colors: [None; SysColorIndex::COUNT],
+        }
+    }
+
+    /// Set the color at the given index in a constant context.
+    pub(crate) const fn with(mut self, index: SysColorIndex, color: SysColor) -> Self {
+        self.colors[index as usize] = Some(color);
+        self
+    }
+
+    /// Take a snapshot of the current system colors.
+    ///
+    /// The provider that [`SysColor::get`] would use is looked up once, and every color is read
+    /// from it, so installing another provider in the middle of the snapshot has no effect on
+    /// it. The provider itself is still queried one index at a time; `Win32Provider` makes a
+    /// separate `GetSysColor` call for each color, so a system color change during the
+    /// snapshot may be only partially reflected.
+    pub fn current() -> Self {
+        Self::from_provider(provider::current())
+    }
+
+    /// Take a snapshot of the colors reported by a provider.
+    pub fn from_provider<P: SysColorProvider + ?Sized>(provider: &P) -> Self {
+        Self::from_fn(|index| provider.sys_color(index))
+    }
+
+    /// Build a palette by calling a closure for every index.
+    fn from_fn(mut f: impl FnMut(SysColorIndex) -> Option<SysColor>) -> Self {
+        let mut palette = Self::new();
+        for (slot, &index) in palette.colors.iter_mut().zip(SysColorIndex::ALL.iter()) {
+            *slot = f(index);
+        }
+        palette
+    }
+
+    /// Get the color at the given index, or `None` if it is not present.
+    pub fn get(&self, index: SysColorIndex) -> Option<SysColor> {
+        self.colors[index as usize]
+    }
+
+    /// Tell whether the color at the given index is present.
+    pub fn is_present(&self, index: SysColorIndex) -> bool {
+        self.colors[index as usize].is_some()
+    }
+
+    /// Set the color at the given index, returning the previous value.
+    ///
+    /// Setting `None` marks the color as not present.
+    pub fn set(&mut self, index: SysColorIndex, color: Option<SysColor>) -> Option<SysColor> {
+        core::mem::replace(&mut self.colors[index as usize], color)
+    }
+
+    /// Mark the color at the given index as not present, returning the previous value.
+    pub fn remove(&mut self, index: SysColorIndex) -> Option<SysColor> {
+        self.set(index, None)
+    }
+
+    /// Iterate over every index along with its color, if present.
+    pub fn iter(&self) -> PaletteIter<'_> {
+        PaletteIter {
+            inner: self.colors.iter().enumerate(),
+        }
+    }
+
+    /// Iterate over the entries that differ between this palette and a newer one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{MockProvider, PaletteChange, SysColorIndex, SysColorPalette};
+    ///
+    /// let mock = MockProvider::new();
+    /// mock.set_rgb(SysColorIndex::Window, 255, 255, 255);
+    /// let old = SysColorPalette::from_provider(&mock);
+    ///
+    /// mock.set_rgb(SysColorIndex::Window, 0, 0, 0);
+    /// let new = SysColorPalette::from_provider(&mock);
+    ///
+    /// let changes: Vec<_> = old.diff(&new).collect();
+    /// assert_eq!(changes.len(), 1);
+    /// assert!(matches!(
+    ///     changes[0],
+    ///     PaletteChange::Changed { index: SysColorIndex::Window, .. }
+    /// ));
+    /// ```
+    ///
+    /// Entries that are equal in both palettes, or missing from both, are skipped.
+    ///
+    /// ```
+    /// use win_syscolor::{PaletteChange, SysColor, SysColorIndex, SysColorPalette};
+    ///
+    /// let red = SysColor::from_rgb(255, 0, 0);
+    /// let blue = SysColor::from_rgb(0, 0, 255);
+    ///
+    /// let mut old = SysColorPalette::new();
+    /// old.set(SysColorIndex::Window, Some(red));
+    /// old.set(SysColorIndex::Menu, Some(blue));
+    ///
+    /// let mut new = SysColorPalette::new();
+    /// new.set(SysColorIndex::Window, Some(red));
+    /// new.set(SysColorIndex::Highlight, Some(blue));
+    ///
+    /// let changes: Vec<_> = old.diff(&new).collect();
+    /// assert_eq!(
+    ///     changes,
+    ///     [
+    ///         PaletteChange::Added { index: SysColorIndex::Highlight, color: blue },
+    ///         PaletteChange::Removed { index: SysColorIndex::Menu, color: blue },
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff<'a>(&'a self, new: &'a SysColorPalette) -> PaletteDiff<'a> {
+        PaletteDiff {
+            inner: self.iter().zip(new.colors.iter()),
+        }
+    }
+}
+
+impl Default for SysColorPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SysColorPalette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<SysColorIndex> for SysColorPalette {
+    type Output = Option<SysColor>;
+
+    fn index(&self, index: SysColorIndex) -> &Self::Output {
+        &self.colors[index as usize]
+    }
+}
+
+impl SysColorProvider for SysColorPalette {
+    fn sys_color(&self, index: SysColorIndex) -> Option<SysColor> {
+        self.get(index)
+    }
+}
+
+impl<'a> IntoIterator for &'a SysColorPalette {
+    type Item = (SysColorIndex, Option<SysColor>);
+    type IntoIter = PaletteIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a [`SysColorPalette`].
+#[derive(Debug, Clone)]
+pub struct PaletteIter<'a> {
+    inner: core::iter::Enumerate<slice::Iter<'a, Option<SysColor>>>,
+}
+
+impl Iterator for PaletteIter<'_> {
+    type Item = (SysColorIndex, Option<SysColor>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(i, &color)| (SysColorIndex::ALL[i], color))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PaletteIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(i, &color)| (SysColorIndex::ALL[i], color))
+    }
+}
+
+impl ExactSizeIterator for PaletteIter<'_> {}
+
+impl FusedIterator for PaletteIter<'_> {}
+
+/// A difference in a single entry between two [`SysColorPalette`]s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PaletteChange {
+    /// The color was not present before, but is now.
+    Added {
+        /// The index of the color.
+        index: SysColorIndex,
+
+        /// The new color.
+        color: SysColor,
+    },
+
+    /// The color was present before, but is not anymore.
+    Removed {
+        /// The index of the color.
+        index: SysColorIndex,
+
+        /// The old color.
+        color: SysColor,
+    },
+
+    /// The color was present in both palettes, but its value changed.
+    Changed {
+        /// The index of the color.
+        index: SysColorIndex,
+
+        /// The old color.
+        old: SysColor,
+
+        /// The new color.
+        new: SysColor,
+    },
+}
+
+impl PaletteChange {
+    /// Get the index of the color that changed.
+    pub fn index(&self) -> SysColorIndex {
+        match *self {
+            PaletteChange::Added { index, .. }
+            | PaletteChange::Removed { index, .. }
+            | PaletteChange::Changed { index, .. } => index,
+        }
+    }
+
+    /// Get the color before the change, if it was present.
+    pub fn before(&self) -> Option<SysColor> {
+        match *self {
+            PaletteChange::Added { .. } => None,
+            PaletteChange::Removed { color, .. } => Some(color),
+            PaletteChange::Changed { old, .. } => Some(old),
+        }
+    }
+
+    /// Get the color after the change, if it is present.
+    pub fn after(&self) -> Option<SysColor> {
+        match *self {
+            PaletteChange::Added { color, .. } => Some(color),
+            PaletteChange::Removed { .. } => None,
+            PaletteChange::Changed { new, .. } => Some(new),
+        }
+    }
+}
+
+/// An iterator over the differences between two [`SysColorPalette`]s.
+///
+/// This is returned by [`SysColorPalette::diff`].
+#[derive(Debug, Clone)]
+pub struct PaletteDiff<'a> {
+    inner: core::iter::Zip<PaletteIter<'a>, slice::Iter<'a, Option<SysColor>>>,
+}
+
+impl Iterator for PaletteDiff<'_> {
+    type Item = PaletteChange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for ((index, old), &new) in &mut self.inner {
+            let change = match (old, new) {
+                (None, Some(color)) => PaletteChange::Added { index, color },
+                (Some(color), None) => PaletteChange::Removed { index, color },
+                (Some(old), Some(new)) if old != new => PaletteChange::Changed { index, old, new },
+                _ => continue,
+            };
+
+            return Some(change);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl FusedIterator for PaletteDiff<'_> {}
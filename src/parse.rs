@@ -0,0 +1,188 @@
+// Boost/Apache2 License
+
+//! Parsing colors from text.
+
+use crate::SysColor;
+
+use core::fmt;
+use core::str::FromStr;
+
+impl FromStr for SysColor {
+    type Err = ParseColorError;
+
+    /// Parse a color from text.
+    ///
+    /// The following notations are accepted:
+    ///
+    /// - `#RGB` and `#RRGGBB` hexadecimal notation.
+    /// - CSS functional notation, `rgb(r, g, b)`.
+    /// - Three space-separated decimal components, as used by the values under the
+    ///   `HKCU\Control Panel\Colors` registry key: `212 208 200`.
+    /// - `COLORREF` literals in `0x00BBGGRR` form. The reserved high byte must be zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ParseColorErrorKind, SysColor};
+    ///
+    /// let color: SysColor = "212 208 200".parse().unwrap();
+    /// assert_eq!(color, "#D4D0C8".parse().unwrap());
+    /// assert_eq!(color, "rgb(212, 208, 200)".parse().unwrap());
+    /// assert_eq!(color, "0x00C8D0D4".parse().unwrap());
+    /// assert_eq!("#FFF".parse::<SysColor>().unwrap().to_string(), "#FFFFFF");
+    ///
+    /// let error = "rgb(+212, 208, 200)".parse::<SysColor>().unwrap_err();
+    /// assert_eq!(error.kind(), ParseColorErrorKind::InvalidComponent);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            Err(ParseColorErrorKind::Empty.into())
+        } else if let Some(digits) = s.strip_prefix('#') {
+            parse_hex(digits)
+        } else if let Some(digits) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            parse_colorref(digits)
+        } else if let Some(args) = strip_function(s, "rgb") {
+            parse_components(args.split(','))
+        } else if s
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_whitespace())
+        {
+            parse_components(s.split_ascii_whitespace())
+        } else {
+            Err(ParseColorErrorKind::UnknownFormat.into())
+        }
+    }
+}
+
+/// The error returned when parsing a [`SysColor`] fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ParseColorError {
+    kind: ParseColorErrorKind,
+}
+
+impl ParseColorError {
+    /// Get the reason that parsing failed.
+    pub fn kind(&self) -> ParseColorErrorKind {
+        self.kind
+    }
+}
+
+impl From<ParseColorErrorKind> for ParseColorError {
+    fn from(kind: ParseColorErrorKind) -> Self {
+        ParseColorError { kind }
+    }
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.kind {
+            ParseColorErrorKind::Empty => "cannot parse color from empty string",
+            ParseColorErrorKind::UnknownFormat => "unrecognized color notation",
+            ParseColorErrorKind::InvalidHex => "invalid hexadecimal color",
+            ParseColorErrorKind::InvalidComponent => {
+                "color component is not an integer from 0 to 255"
+            }
+            ParseColorErrorKind::ComponentCount => "expected three color components",
+            ParseColorErrorKind::ReservedByte => "the high byte of a COLORREF must be zero",
+        };
+
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseColorError {}
+
+/// The reason that parsing a [`SysColor`] failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseColorErrorKind {
+    /// The string was empty.
+    Empty,
+
+    /// The string did not match any of the accepted notations.
+    UnknownFormat,
+
+    /// A hexadecimal color had the wrong number of digits or contained a non-hexadecimal digit.
+    InvalidHex,
+
+    /// A component was not an integer from 0 to 255.
+    InvalidComponent,
+
+    /// There were not exactly three components.
+    ComponentCount,
+
+    /// A `COLORREF` literal had a nonzero reserved high byte.
+    ReservedByte,
+}
+
+/// Parse the digits of a `#RGB` or `#RRGGBB` color.
+fn parse_hex(digits: &str) -> Result<SysColor, ParseColorError> {
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ParseColorErrorKind::InvalidHex.into());
+    }
+
+    let digit = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap();
+    let pair = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+
+    match digits.len() {
+        3 => Ok(SysColor::from_rgb(
+            digit(0) * 0x11,
+            digit(1) * 0x11,
+            digit(2) * 0x11,
+        )),
+        6 => Ok(SysColor::from_rgb(pair(0), pair(2), pair(4))),
+        _ => Err(ParseColorErrorKind::InvalidHex.into()),
+    }
+}
+
+/// Parse the digits of a `0x00BBGGRR` literal.
+fn parse_colorref(digits: &str) -> Result<SysColor, ParseColorError> {
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ParseColorErrorKind::InvalidHex.into());
+    }
+
+    let raw = u32::from_str_radix(digits, 16).unwrap();
+    SysColor::from_colorref(raw).ok_or_else(|| ParseColorErrorKind::ReservedByte.into())
+}
+
+/// Parse exactly three decimal components.
+fn parse_components<'a>(
+    mut components: impl Iterator<Item = &'a str>,
+) -> Result<SysColor, ParseColorError> {
+    let mut next = || {
+        let component = components
+            .next()
+            .ok_or(ParseColorErrorKind::ComponentCount)?
+            .trim();
+
+        // `u8::from_str` also accepts a leading `+`.
+        if !component.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseColorErrorKind::InvalidComponent);
+        }
+
+        component
+            .parse::<u8>()
+            .map_err(|_| ParseColorErrorKind::InvalidComponent)
+    };
+
+    let color = SysColor::from_rgb(next()?, next()?, next()?);
+
+    if components.next().is_some() {
+        return Err(ParseColorErrorKind::ComponentCount.into());
+    }
+
+    Ok(color)
+}
+
+/// Strip a CSS-style function call, returning its arguments.
+fn strip_function<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    let open = s.find('(')?;
+    if !s[..open].trim_end().eq_ignore_ascii_case(name) {
+        return None;
+    }
+
+    s[open + 1..].strip_suffix(')')
+}
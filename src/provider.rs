@@ -0,0 +1,172 @@
+// Boost/Apache2 License
+
+//! Pluggable sources of system colors.
+
+use crate::{SysColor, SysColorIndex};
+
+use core::cell::UnsafeCell;
+use core::hint::spin_loop;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(windows)]
+use crate::OnceBool;
+#[cfg(windows)]
+use windows_sys::Win32::Graphics::Gdi;
+
+/// A source of system colors.
+///
+/// [`SysColor::get`] dispatches through the provider installed with [`set_provider`]. Implement
+/// this trait to supply recorded or synthetic colors instead of the ones reported by the system.
+pub trait SysColorProvider {
+    /// Get the color for the given index, or `None` if it is not present.
+    fn sys_color(&self, index: SysColorIndex) -> Option<SysColor>;
+}
+
+impl<P: SysColorProvider + ?Sized> SysColorProvider for &P {
+    fn sys_color(&self, index: SysColorIndex) -> Option<SysColor> {
+        (**self).sys_color(index)
+    }
+}
+
+/// The provider that reads colors from `GetSysColor`.
+///
+/// This is the default provider on Windows.
+#[cfg(windows)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Win32Provider;
+
+#[cfg(windows)]
+impl SysColorProvider for Win32Provider {
+    fn sys_color(&self, index: SysColorIndex) -> Option<SysColor> {
+        // Cache whether or not each value is present.
+        #[allow(clippy::declare_interior_mutable_const)]
+        const UNINIT: OnceBool = OnceBool::new();
+        static PRESENT: [OnceBool; SysColorIndex::COUNT] = [UNINIT; SysColorIndex::COUNT];
+
+        get_sys_color(index.raw(), &PRESENT[index as usize]).map(SysColor::new)
+    }
+}
+
+/// Install a provider to be used by [`SysColor::get`].
+///
+/// Returns the previously installed provider, if any.
+pub fn set_provider(
+    provider: &'static (dyn SysColorProvider + Sync),
+) -> Option<&'static (dyn SysColorProvider + Sync)> {
+    PROVIDER.replace(Some(provider))
+}
+
+/// Remove the installed provider, restoring the default behavior of [`SysColor::get`].
+///
+/// Returns the previously installed provider, if any.
+pub fn reset_provider() -> Option<&'static (dyn SysColorProvider + Sync)> {
+    PROVIDER.replace(None)
+}
+
+/// Get a color from the installed provider, or the default one.
+pub(crate) fn get(index: SysColorIndex) -> Option<SysColor> {
+    current().sys_color(index)
+}
+
+/// Get the installed provider, or the default one.
+pub(crate) fn current() -> &'static (dyn SysColorProvider + Sync) {
+    PROVIDER.get().unwrap_or(DEFAULT_PROVIDER)
+}
+
+/// The provider used when none has been installed.
+#[cfg(windows)]
+const DEFAULT_PROVIDER: &(dyn SysColorProvider + Sync) = &Win32Provider;
+
+/// The provider used when none has been installed.
+#[cfg(not(windows))]
+const DEFAULT_PROVIDER: &(dyn SysColorProvider + Sync) = &NoProvider;
+
+/// A provider without any colors, used by default where `GetSysColor` is not available.
+#[cfg(not(windows))]
+struct NoProvider;
+
+#[cfg(not(windows))]
+impl SysColorProvider for NoProvider {
+    fn sys_color(&self, _index: SysColorIndex) -> Option<SysColor> {
+        None
+    }
+}
+
+/// The currently installed provider.
+static PROVIDER: ProviderSlot = ProviderSlot::new();
+
+/// A spinlock-protected slot holding the installed provider.
+struct ProviderSlot {
+    /// Whether the slot is currently being accessed.
+    locked: AtomicBool,
+
+    /// The installed provider.
+    provider: UnsafeCell<Option<&'static (dyn SysColorProvider + Sync)>>,
+}
+
+// SAFETY: Access to the inner cell is guarded by `locked`, and the provider itself is `Sync`.
+#[allow(unsafe_code)]
+unsafe impl Sync for ProviderSlot {}
+
+impl ProviderSlot {
+    /// Creates a new, empty `ProviderSlot`.
+    const fn new() -> Self {
+        ProviderSlot {
+            locked: AtomicBool::new(false),
+            provider: UnsafeCell::new(None),
+        }
+    }
+
+    /// Gets the installed provider.
+    fn get(&self) -> Option<&'static (dyn SysColorProvider + Sync)> {
+        self.with(|provider| *provider)
+    }
+
+    /// Replaces the installed provider, returning the old one.
+    fn replace(
+        &self,
+        new: Option<&'static (dyn SysColorProvider + Sync)>,
+    ) -> Option<&'static (dyn SysColorProvider + Sync)> {
+        self.with(|provider| core::mem::replace(provider, new))
+    }
+
+    /// Runs the closure with exclusive access to the provider.
+    #[allow(unsafe_code)]
+    fn with<R>(
+        &self,
+        f: impl FnOnce(&mut Option<&'static (dyn SysColorProvider + Sync)>) -> R,
+    ) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            spin_loop();
+        }
+
+        // SAFETY: We hold the lock, so no one else is accessing the cell.
+        let result = f(unsafe { &mut *self.provider.get() });
+
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+#[cfg(windows)]
+#[allow(unsafe_code)]
+#[inline]
+fn get_sys_color(index: i32, present: &'static OnceBool) -> Option<u32> {
+    // See if the color is present.
+    let present = present.get_or_init(move || {
+        let brush = unsafe { Gdi::GetSysColorBrush(index) };
+        brush != 0
+    });
+
+    if !present {
+        return None;
+    }
+
+    // Get the color.
+    let color = unsafe { Gdi::GetSysColor(index) };
+    Some(color)
+}
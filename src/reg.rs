@@ -0,0 +1,487 @@
+// Boost/Apache2 License
+
+//! Reading and writing registry export (`.reg`) files.
+
+use crate::ini::ends_with_ignore_case;
+use crate::{ParseColorError, SysColor, SysColorIndex, SysColorPalette};
+
+use core::fmt;
+
+/// The header of a version 5 export.
+const REGEDIT5_HEADER: &str = "Windows Registry Editor Version 5.00";
+
+/// The header of a version 4 export.
+const REGEDIT4_HEADER: &str = "REGEDIT4";
+
+/// The key that colors are exported under.
+const COLORS_KEY: &str = r"HKEY_CURRENT_USER\Control Panel\Colors";
+
+/// The end of the path of every key holding colors.
+const COLORS_SUFFIX: &str = r"\Control Panel\Colors";
+
+/// The longest line that can be read from the colors key.
+const MAX_LINE: usize = 512;
+
+/// The format of a registry export file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RegFormat {
+    /// `Windows Registry Editor Version 5.00`, stored as UTF-16LE with a byte order mark.
+    ///
+    /// This is the format written by `regedit` since Windows 2000.
+    #[default]
+    Regedit5,
+
+    /// `REGEDIT4`, stored in the ANSI code page.
+    Regedit4,
+}
+
+impl RegFormat {
+    /// Get the header line that starts files in this format.
+    fn header(self) -> &'static str {
+        match self {
+            RegFormat::Regedit5 => REGEDIT5_HEADER,
+            RegFormat::Regedit4 => REGEDIT4_HEADER,
+        }
+    }
+}
+
+impl SysColorPalette {
+    /// Read the colors of a registry export file.
+    ///
+    /// Files in both [`RegFormat`]s are accepted: UTF-16LE text is recognized by its byte order
+    /// mark, and anything else is read as ANSI. Colors are read from every key whose path ends
+    /// in `\Control Panel\Colors`, matched case-insensitively, so exports of
+    /// `HKEY_USERS\<SID>\Control Panel\Colors` work too. Values are looked up with
+    /// [`SysColorIndex::from_name`]; unknown values and other keys are ignored. A value set to
+    /// `-` removes the color. Keys marked for deletion, as in `[-HKEY_CURRENT_USER\...]`, are
+    /// skipped along with their values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{SysColor, SysColorIndex, SysColorPalette};
+    ///
+    /// let text = "\
+    /// REGEDIT4
+    ///
+    /// [HKEY_CURRENT_USER\\Control Panel\\Colors]
+    /// \"ActiveBorder\"=\"180 180 180\"
+    /// \"Hilight\"=\"0 120 215\"
+    /// ";
+    ///
+    /// let palette = SysColorPalette::from_reg(text.as_bytes()).unwrap();
+    /// assert_eq!(palette.get(SysColorIndex::Highlight), Some(SysColor::from_rgb(0, 120, 215)));
+    /// ```
+    ///
+    /// Values under a deleted key are not read.
+    ///
+    /// ```
+    /// use win_syscolor::{SysColorIndex, SysColorPalette};
+    ///
+    /// let text = "\
+    /// REGEDIT4
+    ///
+    /// [-HKEY_CURRENT_USER\\Control Panel\\Colors]
+    /// \"Window\"=\"1 2 3\"
+    /// ";
+    ///
+    /// let palette = SysColorPalette::from_reg(text.as_bytes()).unwrap();
+    /// assert_eq!(palette.get(SysColorIndex::Window), None);
+    /// ```
+    ///
+    /// Files that do not start with a header are rejected, including empty ones.
+    ///
+    /// ```
+    /// use win_syscolor::{RegErrorKind, SysColorPalette};
+    ///
+    /// let error = SysColorPalette::from_reg(b"").unwrap_err();
+    /// assert_eq!(error.kind(), RegErrorKind::MissingHeader);
+    /// assert_eq!(error.line(), 1);
+    /// ```
+    pub fn from_reg(bytes: &[u8]) -> Result<Self, RegError> {
+        let mut lines = RegLines::new(bytes);
+        let mut palette = SysColorPalette::new();
+        let mut found_header = false;
+        let mut in_colors = false;
+        let mut continued = false;
+
+        while let Some((line, text, truncated)) = lines.next_line()? {
+            let error = |kind| RegError { line, kind };
+            let text = text.trim();
+
+            // Long binary values are continued on the next line.
+            let was_continued = continued;
+            continued = text.ends_with('\\');
+
+            if was_continued || text.is_empty() || text.starts_with(';') {
+                continue;
+            }
+
+            if !found_header {
+                if text.eq_ignore_ascii_case(REGEDIT5_HEADER)
+                    || text.eq_ignore_ascii_case(REGEDIT4_HEADER)
+                {
+                    found_header = true;
+                    continue;
+                }
+
+                return Err(error(RegErrorKind::MissingHeader));
+            }
+
+            if let Some(key) = text.strip_prefix('[') {
+                // A truncated path cannot be checked, and no colors key is that long. Values
+                // under a key that is being deleted are skipped.
+                in_colors = match key.strip_suffix(']') {
+                    Some(path) => !truncated && !path.starts_with('-') && is_colors_key(path),
+                    None if truncated => false,
+                    None => return Err(error(RegErrorKind::UnclosedKey)),
+                };
+                continue;
+            }
+
+            if !in_colors || text.starts_with('@') {
+                continue;
+            }
+
+            if truncated {
+                return Err(error(RegErrorKind::LineTooLong));
+            }
+
+            let (name, value) = split_quoted(text)
+                .and_then(|(name, rest)| Some((name, rest.trim_start().strip_prefix('=')?)))
+                .ok_or_else(|| error(RegErrorKind::InvalidEntry))?;
+            let value = value.trim();
+
+            let index = match SysColorIndex::from_name(name) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            if value == "-" {
+                palette.remove(index);
+                continue;
+            }
+
+            let color = match split_quoted(value) {
+                Some((color, rest)) if rest.trim().is_empty() => color,
+                _ => return Err(error(RegErrorKind::InvalidEntry)),
+            };
+            let color = color
+                .parse::<SysColor>()
+                .map_err(|e| error(RegErrorKind::InvalidColor(e)))?;
+            palette.set(index, Some(color));
+        }
+
+        if found_header {
+            Ok(palette)
+        } else {
+            // Empty files are reported on their first line, like other errors.
+            Err(RegError {
+                line: lines.number.max(1),
+                kind: RegErrorKind::MissingHeader,
+            })
+        }
+    }
+
+    /// Write the colors in this palette as the text of a registry export file.
+    ///
+    /// The present colors are written under `HKEY_CURRENT_USER\Control Panel\Colors` with their
+    /// [registry names](SysColorIndex::registry_name). Lines end with `\r\n`.
+    ///
+    /// The text still has to be encoded as the format requires before it is saved; the
+    /// `std` feature provides `SysColorPalette::save_reg`, which does so.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{RegFormat, SysColor, SysColorIndex, SysColorPalette};
+    ///
+    /// let mut palette = SysColorPalette::new();
+    /// palette.set(SysColorIndex::Highlight, Some(SysColor::from_rgb(0, 120, 215)));
+    ///
+    /// let mut text = String::new();
+    /// palette.write_reg(RegFormat::Regedit4, &mut text).unwrap();
+    ///
+    /// assert_eq!(text, "\
+    /// REGEDIT4\r
+    /// \r
+    /// [HKEY_CURRENT_USER\\Control Panel\\Colors]\r
+    /// \"Hilight\"=\"0 120 215\"\r
+    /// \r
+    /// ");
+    /// assert_eq!(SysColorPalette::from_reg(text.as_bytes()).unwrap(), palette);
+    /// ```
+    pub fn write_reg<W: fmt::Write + ?Sized>(&self, format: RegFormat, out: &mut W) -> fmt::Result {
+        write!(out, "{}\r\n\r\n[{}]\r\n", format.header(), COLORS_KEY)?;
+
+        for (index, color) in self.iter() {
+            if let Some(color) = color {
+                write!(
+                    out,
+                    "\"{}\"=\"{} {} {}\"\r\n",
+                    index.registry_name(),
+                    color.red(),
+                    color.green(),
+                    color.blue()
+                )?;
+            }
+        }
+
+        out.write_str("\r\n")
+    }
+
+    /// Save the colors in this palette as a registry export file.
+    ///
+    /// This writes the text from [`SysColorPalette::write_reg`] in the encoding the format
+    /// requires: UTF-16LE with a byte order mark for [`RegFormat::Regedit5`], and ASCII for
+    /// [`RegFormat::Regedit4`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{RegFormat, SysColor, SysColorIndex, SysColorPalette};
+    ///
+    /// let mut palette = SysColorPalette::new();
+    /// palette.set(SysColorIndex::Window, Some(SysColor::from_rgb(255, 255, 255)));
+    ///
+    /// let mut bytes = Vec::new();
+    /// palette.save_reg(RegFormat::Regedit5, &mut bytes).unwrap();
+    ///
+    /// assert_eq!(&bytes[..4], b"\xFF\xFEW\0");
+    /// assert_eq!(SysColorPalette::from_reg(&bytes).unwrap(), palette);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn save_reg<W: std::io::Write>(&self, format: RegFormat, out: W) -> std::io::Result<()> {
+        let mut encoder = Encoder {
+            out,
+            format,
+            error: None,
+        };
+
+        if format == RegFormat::Regedit5 {
+            encoder.out.write_all(&[0xFF, 0xFE])?;
+        }
+
+        match self.write_reg(format, &mut encoder) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(encoder
+                .error
+                .unwrap_or_else(|| std::io::Error::other("formatter error"))),
+        }
+    }
+}
+
+/// The error returned when reading a registry export file fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RegError {
+    line: usize,
+    kind: RegErrorKind,
+}
+
+impl RegError {
+    /// Get the one-based number of the line where the error was found.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the reason that reading failed.
+    pub fn kind(&self) -> RegErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for RegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            RegErrorKind::InvalidEncoding => f.write_str("UTF-16 text has an odd number of bytes"),
+            RegErrorKind::MissingHeader => f.write_str("not a registry export file"),
+            RegErrorKind::UnclosedKey => f.write_str("key path is missing `]`"),
+            RegErrorKind::InvalidEntry => f.write_str("expected `\"name\"=\"value\"`"),
+            RegErrorKind::InvalidColor(error) => write!(f, "invalid color: {}", error),
+            RegErrorKind::LineTooLong => write!(f, "line is longer than {} characters", MAX_LINE),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RegError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            RegErrorKind::InvalidColor(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The reason that reading a registry export file failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RegErrorKind {
+    /// UTF-16 text had an odd number of bytes.
+    InvalidEncoding,
+
+    /// The file did not start with a `REGEDIT4` or `Windows Registry Editor Version 5.00` line.
+    MissingHeader,
+
+    /// A key path did not end with `]`.
+    UnclosedKey,
+
+    /// A line in the colors key was not a `"name"="value"` entry.
+    InvalidEntry,
+
+    /// A color in the colors key could not be parsed.
+    InvalidColor(ParseColorError),
+
+    /// A line in the colors key was too long to be a color.
+    LineTooLong,
+}
+
+/// Tell whether a key path names a colors key.
+fn is_colors_key(path: &str) -> bool {
+    ends_with_ignore_case(path.trim(), COLORS_SUFFIX)
+}
+
+/// Split a quoted string from the start of the text, returning its contents and the rest.
+///
+/// Escape sequences are skipped over but left in the contents.
+fn split_quoted(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix('"')?;
+    let mut escaped = false;
+
+    for (i, b) in inner.bytes().enumerate() {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some((&inner[..i], &inner[i + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Reads the lines of a registry export file as ASCII.
+///
+/// Characters outside of ASCII are replaced by `?`, since colors and their key never use
+/// them. Lines longer than [`MAX_LINE`] are truncated.
+struct RegLines<'a> {
+    bytes: &'a [u8],
+    utf16: bool,
+    number: usize,
+    buffer: [u8; MAX_LINE],
+}
+
+impl<'a> RegLines<'a> {
+    /// Start reading, detecting the encoding from the byte order mark.
+    fn new(bytes: &'a [u8]) -> Self {
+        let (bytes, utf16) = if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            (rest, true)
+        } else if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+            (rest, false)
+        } else {
+            (bytes, false)
+        };
+
+        RegLines {
+            bytes,
+            utf16,
+            number: 0,
+            buffer: [0; MAX_LINE],
+        }
+    }
+
+    /// Read the next line, returning its number, its text and whether it was truncated.
+    fn next_line(&mut self) -> Result<Option<(usize, &str, bool)>, RegError> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+
+        self.number += 1;
+        let unit_len = if self.utf16 { 2 } else { 1 };
+        let mut len = 0;
+        let mut truncated = false;
+
+        loop {
+            let unit = match self.bytes {
+                [] => break,
+                [lo, hi, ..] if self.utf16 => u16::from_le_bytes([*lo, *hi]),
+                [_] if self.utf16 => {
+                    return Err(RegError {
+                        line: self.number,
+                        kind: RegErrorKind::InvalidEncoding,
+                    })
+                }
+                [b, ..] => u16::from(*b),
+            };
+            self.bytes = &self.bytes[unit_len..];
+
+            let ch = match unit {
+                0x0A => break,
+                0x0D => continue,
+                0x00..=0x7F => unit as u8,
+                _ => b'?',
+            };
+
+            match self.buffer.get_mut(len) {
+                Some(slot) => {
+                    *slot = ch;
+                    len += 1;
+                }
+                None => truncated = true,
+            }
+        }
+
+        // The buffer only ever holds ASCII.
+        let text = core::str::from_utf8(&self.buffer[..len]).unwrap();
+        Ok(Some((self.number, text, truncated)))
+    }
+}
+
+/// Encodes the text of a registry export file into a byte stream.
+#[cfg(feature = "std")]
+struct Encoder<W> {
+    out: W,
+    format: RegFormat,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> fmt::Write for Encoder<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut buffer = [0; 256];
+        let mut len = 0;
+
+        let mut units = s.encode_utf16().peekable();
+        while units.peek().is_some() {
+            for unit in units.by_ref() {
+                let bytes = match self.format {
+                    RegFormat::Regedit5 => unit.to_le_bytes(),
+                    RegFormat::Regedit4 if unit < 0x80 => [unit as u8, 0],
+                    RegFormat::Regedit4 => [b'?', 0],
+                };
+                let bytes = match self.format {
+                    RegFormat::Regedit5 => &bytes[..],
+                    RegFormat::Regedit4 => &bytes[..1],
+                };
+
+                buffer[len..len + bytes.len()].copy_from_slice(bytes);
+                len += bytes.len();
+
+                if len + 2 > buffer.len() {
+                    break;
+                }
+            }
+
+            if let Err(error) = self.out.write_all(&buffer[..len]) {
+                self.error = Some(error);
+                return Err(fmt::Error);
+            }
+            len = 0;
+        }
+
+        Ok(())
+    }
+}
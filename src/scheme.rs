@@ -0,0 +1,429 @@
+// Boost/Apache2 License
+
+//! Reference palettes for well-known Windows color schemes.
+
+use crate::{SysColor, SysColorIndex, SysColorPalette};
+
+use core::fmt;
+
+/// Build a constant palette from a list of colors.
+macro_rules! scheme {
+    ($($name:ident = $r:literal $g:literal $b:literal),* $(,)?) => {
+        SysColorPalette::new()
+            $(.with(SysColorIndex::$name, SysColor::from_rgb($r, $g, $b)))*
+    };
+}
+
+/// Generate the `ColorScheme` enum and the lookup of its palettes.
+macro_rules! generate_schemes {
+    ($($scheme:ident => $palette:ident: $display:literal),* $(,)?) => {
+        /// A well-known Windows color scheme.
+        ///
+        /// Each scheme carries a reference [`SysColorPalette`] with every color present, so that
+        /// Windows-style interfaces can be mocked up without a Windows machine.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use win_syscolor::{ColorScheme, SysColorIndex};
+        ///
+        /// let classic = ColorScheme::WindowsClassic.palette();
+        /// let face = classic.get(SysColorIndex::ButtonFace).unwrap();
+        /// assert_eq!(face.to_string(), "#C0C0C0");
+        /// ```
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[non_exhaustive]
+        pub enum ColorScheme {
+            $(
+                #[doc = concat!("The \"", $display, "\" scheme.")]
+                $scheme,
+            )*
+        }
+
+        impl ColorScheme {
+            /// Every available color scheme, in order.
+            pub const ALL: &'static [ColorScheme] = &[$(ColorScheme::$scheme),*];
+
+            /// Get the reference palette for this scheme.
+            pub fn palette(self) -> &'static SysColorPalette {
+                match self {
+                    $(
+                        ColorScheme::$scheme => &$palette,
+                    )*
+                }
+            }
+
+            /// Get the name of this scheme as it appears in the Windows user interface.
+            pub const fn display_name(self) -> &'static str {
+                match self {
+                    $(
+                        ColorScheme::$scheme => $display,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+generate_schemes! {
+    WindowsStandard => WINDOWS_STANDARD: "Windows Standard",
+    WindowsClassic => WINDOWS_CLASSIC: "Windows Classic",
+    Windows10 => WINDOWS_10: "Windows 10",
+    Windows11 => WINDOWS_11: "Windows 11",
+    HighContrastBlack => HIGH_CONTRAST_BLACK: "High Contrast Black",
+    HighContrastWhite => HIGH_CONTRAST_WHITE: "High Contrast White",
+    HighContrast1 => HIGH_CONTRAST_1: "High Contrast #1",
+    HighContrast2 => HIGH_CONTRAST_2: "High Contrast #2",
+    Brick => BRICK: "Brick",
+    Desert => DESERT: "Desert",
+    RainyDay => RAINY_DAY: "Rainy Day",
+}
+
+impl fmt::Display for ColorScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// The default scheme of Windows 2000 and the Windows Classic theme of Windows XP.
+static WINDOWS_STANDARD: SysColorPalette = scheme! {
+    ScrollBar = 212 208 200,
+    Background = 58 110 165,
+    ActiveCaption = 10 36 106,
+    InactiveCaption = 128 128 128,
+    Menu = 212 208 200,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 212 208 200,
+    InactiveBorder = 212 208 200,
+    AppWorkspace = 128 128 128,
+    Highlight = 10 36 106,
+    HighlightText = 255 255 255,
+    ButtonFace = 212 208 200,
+    ButtonShadow = 128 128 128,
+    GrayText = 128 128 128,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 212 208 200,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 64 64 64,
+    ThreeDLight = 212 208 200,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 166 202 240,
+    GradientInactiveCaption = 192 192 192,
+    MenuHighlight = 10 36 106,
+    MenuBar = 212 208 200,
+};
+
+/// The default scheme of Windows 95, 98 and ME.
+static WINDOWS_CLASSIC: SysColorPalette = scheme! {
+    ScrollBar = 192 192 192,
+    Background = 0 128 128,
+    ActiveCaption = 0 0 128,
+    InactiveCaption = 128 128 128,
+    Menu = 192 192 192,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 192 192 192,
+    InactiveBorder = 192 192 192,
+    AppWorkspace = 128 128 128,
+    Highlight = 0 0 128,
+    HighlightText = 255 255 255,
+    ButtonFace = 192 192 192,
+    ButtonShadow = 128 128 128,
+    GrayText = 128 128 128,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 192 192 192,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 0 0 0,
+    ThreeDLight = 192 192 192,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 16 132 208,
+    GradientInactiveCaption = 181 181 181,
+    MenuHighlight = 0 0 128,
+    MenuBar = 192 192 192,
+};
+
+/// The default scheme of Windows 10.
+static WINDOWS_10: SysColorPalette = scheme! {
+    ScrollBar = 200 200 200,
+    Background = 0 0 0,
+    ActiveCaption = 153 180 209,
+    InactiveCaption = 191 205 219,
+    Menu = 240 240 240,
+    Window = 255 255 255,
+    WindowFrame = 100 100 100,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 0 0 0,
+    ActiveBorder = 180 180 180,
+    InactiveBorder = 244 247 252,
+    AppWorkspace = 171 171 171,
+    Highlight = 0 120 215,
+    HighlightText = 255 255 255,
+    ButtonFace = 240 240 240,
+    ButtonShadow = 160 160 160,
+    GrayText = 109 109 109,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 0 0 0,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 105 105 105,
+    ThreeDLight = 227 227 227,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 102 204,
+    GradientActiveCaption = 185 209 234,
+    GradientInactiveCaption = 215 228 242,
+    MenuHighlight = 0 120 215,
+    MenuBar = 240 240 240,
+};
+
+/// The default scheme of Windows 11, which keeps the classic colors of Windows 10.
+static WINDOWS_11: SysColorPalette = WINDOWS_10;
+
+/// The "High Contrast Black" scheme.
+static HIGH_CONTRAST_BLACK: SysColorPalette = scheme! {
+    ScrollBar = 0 0 0,
+    Background = 0 0 0,
+    ActiveCaption = 128 0 128,
+    InactiveCaption = 0 128 0,
+    Menu = 0 0 0,
+    Window = 0 0 0,
+    WindowFrame = 255 255 255,
+    MenuText = 255 255 255,
+    WindowText = 255 255 255,
+    CaptionText = 255 255 255,
+    ActiveBorder = 255 255 0,
+    InactiveBorder = 0 128 0,
+    AppWorkspace = 0 0 0,
+    Highlight = 128 0 128,
+    HighlightText = 255 255 255,
+    ButtonFace = 0 0 0,
+    ButtonShadow = 128 128 128,
+    GrayText = 0 255 0,
+    ButtonText = 255 255 255,
+    InactiveCaptionText = 255 255 255,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 255 255 255,
+    ThreeDLight = 192 192 192,
+    InfoText = 255 255 255,
+    InfoBackground = 0 0 0,
+    HotLight = 255 255 0,
+    GradientActiveCaption = 128 0 128,
+    GradientInactiveCaption = 0 128 0,
+    MenuHighlight = 128 0 128,
+    MenuBar = 0 0 0,
+};
+
+/// The "High Contrast White" scheme.
+static HIGH_CONTRAST_WHITE: SysColorPalette = scheme! {
+    ScrollBar = 255 255 255,
+    Background = 255 255 255,
+    ActiveCaption = 0 0 0,
+    InactiveCaption = 255 255 255,
+    Menu = 255 255 255,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 128 128 128,
+    InactiveBorder = 192 192 192,
+    AppWorkspace = 128 128 128,
+    Highlight = 0 0 0,
+    HighlightText = 255 255 255,
+    ButtonFace = 255 255 255,
+    ButtonShadow = 128 128 128,
+    GrayText = 0 128 0,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 0 0 0,
+    ButtonHighlight = 192 192 192,
+    ThreeDDarkShadow = 0 0 0,
+    ThreeDLight = 255 255 255,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 255,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 0 0 0,
+    GradientInactiveCaption = 255 255 255,
+    MenuHighlight = 0 0 0,
+    MenuBar = 255 255 255,
+};
+
+/// The "High Contrast #1" scheme, with yellow text on black.
+static HIGH_CONTRAST_1: SysColorPalette = scheme! {
+    ScrollBar = 0 0 0,
+    Background = 0 0 0,
+    ActiveCaption = 0 0 255,
+    InactiveCaption = 0 255 255,
+    Menu = 0 0 0,
+    Window = 0 0 0,
+    WindowFrame = 255 255 255,
+    MenuText = 255 255 255,
+    WindowText = 255 255 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 0 0 255,
+    InactiveBorder = 0 255 255,
+    AppWorkspace = 0 0 0,
+    Highlight = 0 0 255,
+    HighlightText = 255 255 255,
+    ButtonFace = 0 0 0,
+    ButtonShadow = 128 128 128,
+    GrayText = 0 255 0,
+    ButtonText = 255 255 255,
+    InactiveCaptionText = 0 0 0,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 255 255 255,
+    ThreeDLight = 192 192 192,
+    InfoText = 255 255 0,
+    InfoBackground = 0 0 0,
+    HotLight = 0 255 255,
+    GradientActiveCaption = 0 0 255,
+    GradientInactiveCaption = 0 255 255,
+    MenuHighlight = 0 0 255,
+    MenuBar = 0 0 0,
+};
+
+/// The "High Contrast #2" scheme, with green text on black.
+static HIGH_CONTRAST_2: SysColorPalette = scheme! {
+    ScrollBar = 0 0 0,
+    Background = 0 0 0,
+    ActiveCaption = 0 255 255,
+    InactiveCaption = 0 0 255,
+    Menu = 0 0 0,
+    Window = 0 0 0,
+    WindowFrame = 255 255 255,
+    MenuText = 0 255 0,
+    WindowText = 0 255 0,
+    CaptionText = 0 0 0,
+    ActiveBorder = 0 255 255,
+    InactiveBorder = 0 0 255,
+    AppWorkspace = 0 0 0,
+    Highlight = 0 255 255,
+    HighlightText = 0 0 0,
+    ButtonFace = 0 0 0,
+    ButtonShadow = 128 128 128,
+    GrayText = 255 255 0,
+    ButtonText = 0 255 0,
+    InactiveCaptionText = 255 255 255,
+    ButtonHighlight = 255 255 255,
+    ThreeDDarkShadow = 255 255 255,
+    ThreeDLight = 192 192 192,
+    InfoText = 0 255 0,
+    InfoBackground = 0 0 0,
+    HotLight = 255 255 0,
+    GradientActiveCaption = 0 255 255,
+    GradientInactiveCaption = 0 0 255,
+    MenuHighlight = 0 255 255,
+    MenuBar = 0 0 0,
+};
+
+/// The "Brick" scheme.
+static BRICK: SysColorPalette = scheme! {
+    ScrollBar = 225 224 210,
+    Background = 66 0 0,
+    ActiveCaption = 128 0 0,
+    InactiveCaption = 141 137 97,
+    Menu = 194 191 165,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 194 191 165,
+    InactiveBorder = 194 191 165,
+    AppWorkspace = 141 137 97,
+    Highlight = 128 0 0,
+    HighlightText = 255 255 255,
+    ButtonFace = 194 191 165,
+    ButtonShadow = 141 137 97,
+    GrayText = 141 137 97,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 194 191 165,
+    ButtonHighlight = 225 224 210,
+    ThreeDDarkShadow = 0 0 0,
+    ThreeDLight = 194 191 165,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 192 128 128,
+    GradientInactiveCaption = 194 191 165,
+    MenuHighlight = 128 0 0,
+    MenuBar = 194 191 165,
+};
+
+/// The "Desert" scheme.
+static DESERT: SysColorPalette = scheme! {
+    ScrollBar = 234 230 221,
+    Background = 162 141 104,
+    ActiveCaption = 0 128 128,
+    InactiveCaption = 162 141 104,
+    Menu = 213 204 187,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 213 204 187,
+    InactiveBorder = 213 204 187,
+    AppWorkspace = 162 141 104,
+    Highlight = 0 128 128,
+    HighlightText = 255 255 255,
+    ButtonFace = 213 204 187,
+    ButtonShadow = 162 141 104,
+    GrayText = 162 141 104,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 213 204 187,
+    ButtonHighlight = 234 230 221,
+    ThreeDDarkShadow = 0 0 0,
+    ThreeDLight = 213 204 187,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 0 160 160,
+    GradientInactiveCaption = 213 204 187,
+    MenuHighlight = 0 128 128,
+    MenuBar = 213 204 187,
+};
+
+/// The "Rainy Day" scheme.
+static RAINY_DAY: SysColorPalette = scheme! {
+    ScrollBar = 192 200 216,
+    Background = 0 0 0,
+    ActiveCaption = 79 101 125,
+    InactiveCaption = 128 152 176,
+    Menu = 128 152 176,
+    Window = 255 255 255,
+    WindowFrame = 0 0 0,
+    MenuText = 0 0 0,
+    WindowText = 0 0 0,
+    CaptionText = 255 255 255,
+    ActiveBorder = 128 152 176,
+    InactiveBorder = 128 152 176,
+    AppWorkspace = 79 101 125,
+    Highlight = 79 101 125,
+    HighlightText = 255 255 255,
+    ButtonFace = 128 152 176,
+    ButtonShadow = 79 101 125,
+    GrayText = 79 101 125,
+    ButtonText = 0 0 0,
+    InactiveCaptionText = 0 0 0,
+    ButtonHighlight = 192 200 216,
+    ThreeDDarkShadow = 0 0 0,
+    ThreeDLight = 128 152 176,
+    InfoText = 0 0 0,
+    InfoBackground = 255 255 225,
+    HotLight = 0 0 128,
+    GradientActiveCaption = 128 152 176,
+    GradientInactiveCaption = 192 200 216,
+    MenuHighlight = 79 101 125,
+    MenuBar = 128 152 176,
+};
@@ -0,0 +1,416 @@
+// Boost/Apache2 License
+
+//! `serde` support for the types in this crate.
+//!
+//! By default, [`SysColor`] is serialized as a `#RRGGBB` string in human-readable formats and as
+//! its raw `COLORREF` value otherwise. When deserializing from a human-readable format, any string
+//! accepted by the `FromStr` implementation of [`SysColor`] and a `{ red, green, blue }` struct
+//! are accepted. The [`hex`] and [`rgb`] modules
+//! can be used with `#[serde(with = "...")]` to pick one representation explicitly.
+//!
+//! [`SysColorIndex`] is serialized by name and [`SysColorPalette`] as a map from names to the
+//! colors that are present.
+
+use crate::{SysColor, SysColorIndex, SysColorPalette};
+
+use ::serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+use core::fmt;
+
+/// The names of the fields in the struct representation of a color.
+const FIELDS: &[&str] = &["red", "green", "blue"];
+
+impl Serialize for SysColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            hex::serialize(self, serializer)
+        } else {
+            serializer.serialize_u32(self.raw())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SysColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ColorVisitor)
+        } else {
+            let raw = u32::deserialize(deserializer)?;
+            SysColor::from_colorref(raw).ok_or_else(|| {
+                de::Error::invalid_value(
+                    de::Unexpected::Unsigned(raw.into()),
+                    &"a COLORREF with a zero high byte",
+                )
+            })
+        }
+    }
+}
+
+/// Serialize a [`SysColor`] as a `#RRGGBB` string.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use win_syscolor::SysColor;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Accent {
+///     #[serde(with = "win_syscolor::serde_format::hex")]
+///     color: SysColor,
+/// }
+///
+/// let accent = Accent { color: SysColor::from_rgb(0, 120, 215) };
+/// assert_eq!(serde_json::to_string(&accent).unwrap(), r##"{"color":"#0078D7"}"##);
+///
+/// // Any notation accepted by `FromStr` can be read back.
+/// assert_eq!(serde_json::from_str::<Accent>(r##"{"color":"#0078D7"}"##).unwrap(), accent);
+/// assert_eq!(serde_json::from_str::<Accent>(r#"{"color":"0 120 215"}"#).unwrap(), accent);
+/// assert_eq!(serde_json::from_str::<Accent>(r#"{"color":"rgb(0, 120, 215)"}"#).unwrap(), accent);
+///
+/// // Other representations are rejected.
+/// assert!(serde_json::from_str::<Accent>(r#"{"color":[0, 120, 215]}"#).is_err());
+/// assert!(serde_json::from_str::<Accent>(r##"{"color":"#0078D"}"##).is_err());
+/// ```
+pub mod hex {
+    use super::*;
+
+    /// Serialize a color as a `#RRGGBB` string.
+    pub fn serialize<S: Serializer>(color: &SysColor, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(color)
+    }
+
+    /// Deserialize a color from a string.
+    ///
+    /// Any notation accepted by the `FromStr` implementation of [`SysColor`] can be used.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SysColor, D::Error> {
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+/// Serialize a [`SysColor`] as a `{ red, green, blue }` struct.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use win_syscolor::SysColor;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Accent {
+///     #[serde(with = "win_syscolor::serde_format::rgb")]
+///     color: SysColor,
+/// }
+///
+/// let accent = Accent { color: SysColor::from_rgb(0, 120, 215) };
+/// let json = r#"{"color":{"red":0,"green":120,"blue":215}}"#;
+///
+/// assert_eq!(serde_json::to_string(&accent).unwrap(), json);
+/// assert_eq!(serde_json::from_str::<Accent>(json).unwrap(), accent);
+///
+/// // Every field must be given exactly once.
+/// let error = serde_json::from_str::<Accent>(r#"{"color":{"red":0,"red":1,"green":120,"blue":215}}"#)
+///     .unwrap_err();
+/// assert!(error.to_string().contains("duplicate field `red`"));
+///
+/// let error = serde_json::from_str::<Accent>(r#"{"color":{"red":0,"green":120}}"#).unwrap_err();
+/// assert!(error.to_string().contains("missing field `blue`"));
+/// ```
+pub mod rgb {
+    use super::*;
+
+    /// Serialize a color as a `{ red, green, blue }` struct.
+    pub fn serialize<S: Serializer>(color: &SysColor, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SysColor", 3)?;
+        state.serialize_field("red", &color.red())?;
+        state.serialize_field("green", &color.green())?;
+        state.serialize_field("blue", &color.blue())?;
+        state.end()
+    }
+
+    /// Deserialize a color from a `{ red, green, blue }` struct.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SysColor, D::Error> {
+        deserializer.deserialize_struct("SysColor", FIELDS, ColorVisitor)
+    }
+}
+
+/// Visits any of the representations of a color.
+struct ColorVisitor;
+
+impl<'de> Visitor<'de> for ColorVisitor {
+    type Value = SysColor;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a color string or a struct with red, green and blue fields")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut next = |i| {
+            seq.next_element::<u8>()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))
+        };
+
+        let red = next(0)?;
+        let green = next(1)?;
+        let blue = next(2)?;
+        Ok(SysColor::from_rgb(red, green, blue))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = [None; 3];
+
+        while let Some(key) = map.next_key::<Field>()? {
+            let slot = &mut components[key as usize];
+            if slot.is_some() {
+                return Err(de::Error::duplicate_field(FIELDS[key as usize]));
+            }
+            *slot = Some(map.next_value::<u8>()?);
+        }
+
+        let get = |i: usize| components[i].ok_or_else(|| de::Error::missing_field(FIELDS[i]));
+        Ok(SysColor::from_rgb(get(0)?, get(1)?, get(2)?))
+    }
+}
+
+/// A field in the struct representation of a color.
+#[derive(Copy, Clone)]
+enum Field {
+    Red,
+    Green,
+    Blue,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl<'de> Visitor<'de> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("`red`, `green` or `blue`")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match v {
+                    "red" => Ok(Field::Red),
+                    "green" => Ok(Field::Green),
+                    "blue" => Ok(Field::Blue),
+                    _ => Err(E::unknown_field(v, FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+impl Serialize for SysColorIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for SysColorIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IndexVisitor;
+
+        impl<'de> Visitor<'de> for IndexVisitor {
+            type Value = SysColorIndex;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("the name of a system color")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                SysColorIndex::from_name(v)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(IndexVisitor)
+    }
+}
+
+impl Serialize for SysColorPalette {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.iter().filter(|(_, color)| color.is_some()).count();
+        let mut map = serializer.serialize_map(Some(len))?;
+
+        for (index, color) in self {
+            if let Some(color) = color {
+                map.serialize_entry(&index, &color)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for SysColorPalette {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PaletteVisitor;
+
+        impl<'de> Visitor<'de> for PaletteVisitor {
+            type Value = SysColorPalette;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map from system color names to colors")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut palette = SysColorPalette::new();
+
+                while let Some((index, color)) = map.next_entry::<SysColorIndex, SysColor>()? {
+                    if palette.set(index, Some(color)).is_some() {
+                        return Err(de::Error::custom(format_args!(
+                            "duplicate system color `{}`",
+                            index.name()
+                        )));
+                    }
+                }
+
+                Ok(palette)
+            }
+        }
+
+        deserializer.deserialize_map(PaletteVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SysColor, SysColorIndex, SysColorPalette};
+
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    const ACCENT: SysColor = SysColor::from_rgb(0, 120, 215);
+
+    #[test]
+    fn color_readable() {
+        assert_tokens(&ACCENT.readable(), &[Token::Str("#0078D7")]);
+        assert_de_tokens(&ACCENT.readable(), &[Token::Str("0x00D77800")]);
+
+        assert_de_tokens(
+            &ACCENT.readable(),
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U8(0),
+                Token::U8(120),
+                Token::U8(215),
+                Token::SeqEnd,
+            ],
+        );
+
+        assert_de_tokens(
+            &ACCENT.readable(),
+            &[
+                Token::Struct {
+                    name: "SysColor",
+                    len: 3,
+                },
+                Token::Str("blue"),
+                Token::U8(215),
+                Token::Str("red"),
+                Token::U8(0),
+                Token::Str("green"),
+                Token::U8(120),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn color_readable_errors() {
+        assert_de_tokens_error::<serde_test::Readable<SysColor>>(
+            &[Token::Str("#12345")],
+            "invalid hexadecimal color",
+        );
+
+        assert_de_tokens_error::<serde_test::Readable<SysColor>>(
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(0),
+                Token::U8(120),
+                Token::SeqEnd,
+            ],
+            "invalid length 2, expected a color string or a struct with red, green and blue fields",
+        );
+
+        assert_de_tokens_error::<serde_test::Readable<SysColor>>(
+            &[
+                Token::Struct {
+                    name: "SysColor",
+                    len: 3,
+                },
+                Token::Str("red"),
+                Token::U8(0),
+                Token::Str("red"),
+            ],
+            "duplicate field `red`",
+        );
+
+        assert_de_tokens_error::<serde_test::Readable<SysColor>>(
+            &[
+                Token::Struct {
+                    name: "SysColor",
+                    len: 3,
+                },
+                Token::Str("alpha"),
+            ],
+            "unknown field `alpha`, expected one of `red`, `green`, `blue`",
+        );
+    }
+
+    #[test]
+    fn color_compact() {
+        assert_tokens(&ACCENT.compact(), &[Token::U32(0x00D7_7800)]);
+
+        assert_de_tokens_error::<serde_test::Compact<SysColor>>(
+            &[Token::U32(0x0100_0000)],
+            "invalid value: integer `16777216`, expected a COLORREF with a zero high byte",
+        );
+    }
+
+    #[test]
+    fn index() {
+        assert_tokens(&SysColorIndex::ButtonFace, &[Token::Str("ButtonFace")]);
+        assert_de_tokens(&SysColorIndex::ButtonFace, &[Token::Str("COLOR_BTNFACE")]);
+
+        assert_de_tokens_error::<SysColorIndex>(
+            &[Token::Str("Chartreuse")],
+            "invalid value: string \"Chartreuse\", expected the name of a system color",
+        );
+    }
+
+    #[test]
+    fn palette() {
+        let mut palette = SysColorPalette::new();
+        palette.set(SysColorIndex::Window, Some(ACCENT));
+
+        assert_tokens(
+            &palette.readable(),
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("Window"),
+                Token::Str("#0078D7"),
+                Token::MapEnd,
+            ],
+        );
+
+        assert_de_tokens_error::<serde_test::Readable<SysColorPalette>>(
+            &[
+                Token::Map { len: Some(2) },
+                Token::Str("Window"),
+                Token::Str("#0078D7"),
+                Token::Str("COLOR_WINDOW"),
+                Token::Str("#000000"),
+            ],
+            "duplicate system color `Window`",
+        );
+    }
+}
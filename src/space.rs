@@ -0,0 +1,531 @@
+// Boost/Apache2 License
+
+//! Conversions between [`SysColor`] and other color spaces.
+//!
+//! Every color space here uses `f32` components. Converting into [`SysColor`] clamps each channel
+//! to the sRGB gamut and rounds it to the nearest 8-bit value, so converting a [`SysColor`] out
+//! and back in yields the original color.
+
+use crate::SysColor;
+
+/// A color in linear-light sRGB, with components from `0.0` to `1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{LinearRgb, SysColor};
+///
+/// let linear = LinearRgb::from(SysColor::from_rgb(255, 0, 0));
+/// assert_eq!((linear.red, linear.green, linear.blue), (1.0, 0.0, 0.0));
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct LinearRgb {
+    /// The red component.
+    pub red: f32,
+
+    /// The green component.
+    pub green: f32,
+
+    /// The blue component.
+    pub blue: f32,
+}
+
+/// A color in the HSL (hue, saturation, lightness) model over sRGB.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{Hsl, SysColor};
+///
+/// let hsl = Hsl::from(SysColor::from_rgb(255, 0, 0));
+/// assert_eq!((hsl.hue, hsl.saturation, hsl.lightness), (0.0, 1.0, 0.5));
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Hsl {
+    /// The hue, in degrees from `0.0` to `360.0`.
+    pub hue: f32,
+
+    /// The saturation, from `0.0` to `1.0`.
+    pub saturation: f32,
+
+    /// The lightness, from `0.0` to `1.0`.
+    pub lightness: f32,
+}
+
+/// A color in the HSV (hue, saturation, value) model over sRGB.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{Hsv, SysColor};
+///
+/// let hsv = Hsv::from(SysColor::from_rgb(255, 0, 0));
+/// assert_eq!((hsv.hue, hsv.saturation, hsv.value), (0.0, 1.0, 1.0));
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Hsv {
+    /// The hue, in degrees from `0.0` to `360.0`.
+    pub hue: f32,
+
+    /// The saturation, from `0.0` to `1.0`.
+    pub saturation: f32,
+
+    /// The value, from `0.0` to `1.0`.
+    pub value: f32,
+}
+
+/// A color in the CIE 1931 XYZ color space, relative to the D65 white point.
+///
+/// The `y` component is the relative luminance, from `0.0` to `1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{SysColor, Xyz};
+///
+/// let white = Xyz::from(SysColor::from_rgb(255, 255, 255));
+/// assert!((white.y - 1.0).abs() < 0.001);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Xyz {
+    /// The X component.
+    pub x: f32,
+
+    /// The Y component.
+    pub y: f32,
+
+    /// The Z component.
+    pub z: f32,
+}
+
+/// A color in the CIELAB color space, relative to the D65 white point.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{Lab, SysColor};
+///
+/// let white = Lab::from(SysColor::from_rgb(255, 255, 255));
+/// assert!((white.l - 100.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Lab {
+    /// The lightness, from `0.0` to `100.0`.
+    pub l: f32,
+
+    /// The green-red axis.
+    pub a: f32,
+
+    /// The blue-yellow axis.
+    pub b: f32,
+}
+
+/// A color in the Oklab perceptual color space.
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{Oklab, SysColor};
+///
+/// let white = Oklab::from(SysColor::from_rgb(255, 255, 255));
+/// assert!((white.l - 1.0).abs() < 0.001);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Oklab {
+    /// The perceived lightness, from `0.0` to `1.0`.
+    pub l: f32,
+
+    /// The green-red axis.
+    pub a: f32,
+
+    /// The blue-yellow axis.
+    pub b: f32,
+}
+
+/// A color in the Oklch color space, the polar form of [`Oklab`].
+///
+/// # Examples
+///
+/// ```
+/// use win_syscolor::{Oklch, SysColor};
+///
+/// let gray = Oklch::from(SysColor::from_rgb(128, 128, 128));
+/// assert!(gray.chroma < 0.001);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Oklch {
+    /// The perceived lightness, from `0.0` to `1.0`.
+    pub l: f32,
+
+    /// The chroma.
+    pub chroma: f32,
+
+    /// The hue, in degrees from `0.0` to `360.0`.
+    pub hue: f32,
+}
+
+impl From<SysColor> for LinearRgb {
+    fn from(color: SysColor) -> Self {
+        LinearRgb {
+            red: decode(color.red()),
+            green: decode(color.green()),
+            blue: decode(color.blue()),
+        }
+    }
+}
+
+impl From<LinearRgb> for SysColor {
+    fn from(color: LinearRgb) -> Self {
+        SysColor::from_rgb(encode(color.red), encode(color.green), encode(color.blue))
+    }
+}
+
+impl From<SysColor> for Hsl {
+    fn from(color: SysColor) -> Self {
+        let [r, g, b] = unit(color);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Hsl {
+            hue: hue(r, g, b, max, chroma),
+            saturation,
+            lightness,
+        }
+    }
+}
+
+impl From<Hsl> for SysColor {
+    fn from(color: Hsl) -> Self {
+        let lightness = clamp(color.lightness);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * clamp(color.saturation);
+        from_hue(color.hue, chroma, lightness - chroma / 2.0)
+    }
+}
+
+impl From<SysColor> for Hsv {
+    fn from(color: SysColor) -> Self {
+        let [r, g, b] = unit(color);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let chroma = max - min;
+
+        Hsv {
+            hue: hue(r, g, b, max, chroma),
+            saturation: if max == 0.0 { 0.0 } else { chroma / max },
+            value: max,
+        }
+    }
+}
+
+impl From<Hsv> for SysColor {
+    fn from(color: Hsv) -> Self {
+        let value = clamp(color.value);
+        let chroma = value * clamp(color.saturation);
+        from_hue(color.hue, chroma, value - chroma)
+    }
+}
+
+impl From<LinearRgb> for Xyz {
+    fn from(color: LinearRgb) -> Self {
+        let LinearRgb { red, green, blue } = color;
+
+        Xyz {
+            x: 0.412_456_4 * red + 0.357_576_1 * green + 0.180_437_5 * blue,
+            y: 0.212_672_9 * red + 0.715_152_2 * green + 0.072_175 * blue,
+            z: 0.019_333_9 * red + 0.119_192 * green + 0.950_304_1 * blue,
+        }
+    }
+}
+
+impl From<Xyz> for LinearRgb {
+    fn from(color: Xyz) -> Self {
+        let Xyz { x, y, z } = color;
+
+        LinearRgb {
+            red: 3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+            green: -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+            blue: 0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+        }
+    }
+}
+
+impl From<SysColor> for Xyz {
+    fn from(color: SysColor) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+impl From<Xyz> for SysColor {
+    fn from(color: Xyz) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+/// The D65 reference white in XYZ.
+const WHITE: Xyz = Xyz {
+    x: 0.950_47,
+    y: 1.0,
+    z: 1.088_83,
+};
+
+/// The threshold between the linear and cubic parts of the CIELAB transfer function.
+const DELTA: f32 = 6.0 / 29.0;
+
+impl From<Xyz> for Lab {
+    fn from(color: Xyz) -> Self {
+        let f = |t: f32| {
+            if t > DELTA * DELTA * DELTA {
+                libm::cbrtf(t)
+            } else {
+                t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+            }
+        };
+
+        let (fx, fy, fz) = (
+            f(color.x / WHITE.x),
+            f(color.y / WHITE.y),
+            f(color.z / WHITE.z),
+        );
+
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+}
+
+impl From<Lab> for Xyz {
+    fn from(color: Lab) -> Self {
+        let f = |t: f32| {
+            if t > DELTA {
+                t * t * t
+            } else {
+                3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+            }
+        };
+
+        let fy = (color.l + 16.0) / 116.0;
+
+        Xyz {
+            x: WHITE.x * f(fy + color.a / 500.0),
+            y: WHITE.y * f(fy),
+            z: WHITE.z * f(fy - color.b / 200.0),
+        }
+    }
+}
+
+impl From<SysColor> for Lab {
+    fn from(color: SysColor) -> Self {
+        Xyz::from(color).into()
+    }
+}
+
+impl From<Lab> for SysColor {
+    fn from(color: Lab) -> Self {
+        Xyz::from(color).into()
+    }
+}
+
+impl From<LinearRgb> for Oklab {
+    fn from(color: LinearRgb) -> Self {
+        let LinearRgb { red, green, blue } = color;
+
+        let l = libm::cbrtf(0.412_221_46 * red + 0.536_332_55 * green + 0.051_445_995 * blue);
+        let m = libm::cbrtf(0.211_903_5 * red + 0.680_699_5 * green + 0.107_396_96 * blue);
+        let s = libm::cbrtf(0.088_302_46 * red + 0.281_718_85 * green + 0.629_978_7 * blue);
+
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+}
+
+impl From<Oklab> for LinearRgb {
+    fn from(color: Oklab) -> Self {
+        let Oklab { l, a, b } = color;
+
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+
+        let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+        LinearRgb {
+            red: 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            green: -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            blue: -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        }
+    }
+}
+
+impl From<SysColor> for Oklab {
+    fn from(color: SysColor) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+impl From<Oklab> for SysColor {
+    fn from(color: Oklab) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(color: Oklab) -> Self {
+        let chroma = libm::sqrtf(color.a * color.a + color.b * color.b);
+        let hue = libm::atan2f(color.b, color.a).to_degrees();
+
+        Oklch {
+            l: color.l,
+            chroma,
+            hue: if hue < 0.0 { hue + 360.0 } else { hue },
+        }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(color: Oklch) -> Self {
+        let hue = color.hue.to_radians();
+
+        Oklab {
+            l: color.l,
+            a: color.chroma * libm::cosf(hue),
+            b: color.chroma * libm::sinf(hue),
+        }
+    }
+}
+
+impl From<SysColor> for Oklch {
+    fn from(color: SysColor) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+impl From<Oklch> for SysColor {
+    fn from(color: Oklch) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+/// Convert a gamma-encoded 8-bit channel to linear light.
+fn decode(channel: u8) -> f32 {
+    let c = f32::from(channel) / 255.0;
+
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        libm::powf((c + 0.055) / 1.055, 2.4)
+    }
+}
+
+/// Convert a linear-light channel to a gamma-encoded 8-bit value.
+fn encode(channel: f32) -> u8 {
+    let c = clamp(channel);
+
+    let c = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * libm::powf(c, 1.0 / 2.4) - 0.055
+    };
+
+    quantize(c)
+}
+
+/// Get the gamma-encoded channels of a color from `0.0` to `1.0`.
+fn unit(color: SysColor) -> [f32; 3] {
+    [color.red(), color.green(), color.blue()].map(|c| f32::from(c) / 255.0)
+}
+
+/// Compute the hue of a gamma-encoded color, in degrees.
+fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+
+    let sector = if max == r {
+        (g - b) / chroma
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+
+    let hue = sector * 60.0;
+    if hue < 0.0 {
+        hue + 360.0
+    } else {
+        hue
+    }
+}
+
+/// Build a color from its hue, chroma and the amount to add to every channel.
+fn from_hue(hue: f32, chroma: f32, offset: f32) -> SysColor {
+    let sector = libm::fmodf(libm::fmodf(hue, 360.0) + 360.0, 360.0) / 60.0;
+    let x = chroma * (1.0 - (libm::fmodf(sector, 2.0) - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    SysColor::from_rgb(
+        quantize(r + offset),
+        quantize(g + offset),
+        quantize(b + offset),
+    )
+}
+
+/// Round a channel from `0.0` to `1.0` to the nearest 8-bit value.
+fn quantize(channel: f32) -> u8 {
+    libm::roundf(clamp(channel) * 255.0) as u8
+}
+
+/// Clamp a value to the range from `0.0` to `1.0`, mapping NaN to `0.0`.
+pub(crate) fn clamp(value: f32) -> f32 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hsl, Hsv, Lab, LinearRgb, Oklab, Oklch, Xyz};
+    use crate::SysColor;
+
+    /// Check that converting colors into a space and back yields the original colors.
+    fn round_trip<T: From<SysColor>>()
+    where
+        SysColor: From<T>,
+    {
+        for raw in (0..0x0100_0000).step_by(0x010101 * 3 + 17) {
+            let color = SysColor::from_colorref(raw).unwrap();
+            assert_eq!(SysColor::from(T::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip::<LinearRgb>();
+        round_trip::<Hsl>();
+        round_trip::<Hsv>();
+        round_trip::<Xyz>();
+        round_trip::<Lab>();
+        round_trip::<Oklab>();
+        round_trip::<Oklch>();
+    }
+}
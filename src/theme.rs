@@ -0,0 +1,567 @@
+// Boost/Apache2 License
+
+//! Reading and writing Windows `.theme` files.
+
+use crate::ini::{is_section, Line, Lines};
+use crate::{ParseColorError, Rgba, SysColor, SysColorIndex, SysColorPalette};
+
+use core::fmt;
+
+/// The section holding the system colors.
+const COLORS: &str = r"Control Panel\Colors";
+
+/// The header of the section holding the system colors.
+const COLORS_HEADER: &str = "[Control Panel\\Colors]";
+
+/// The registry name of the color in slot 25, which has no [`SysColorIndex`].
+const BUTTON_ALTERNATE_FACE: &str = "ButtonAlternateFace";
+
+/// The section that marks a file as a theme, with the value Windows expects.
+const MASTER_THEME_SELECTOR: &str = "[MasterThemeSelector]\r\nMTSM=RJSPBS\r\n";
+
+/// The section holding the desktop background.
+pub(crate) const DESKTOP: &str = r"Control Panel\Desktop";
+
+/// The section holding the theme name.
+const THEME: &str = "Theme";
+
+/// The section holding the visual style.
+pub(crate) const VISUAL_STYLES: &str = "VisualStyles";
+
+/// The contents of a Windows `.theme` file.
+///
+/// Strings borrow from the parsed text and are kept exactly as written, so paths may still
+/// contain environment variables such as `%SystemRoot%` and names may be resource references
+/// such as `@themeui.dll,-2013`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Theme<'a> {
+    /// The `DisplayName` of the `[Theme]` section.
+    pub display_name: Option<&'a str>,
+
+    /// The colors of the `[Control Panel\Colors]` section.
+    pub palette: SysColorPalette,
+
+    /// The `[VisualStyles]` section.
+    pub visual_styles: VisualStyles<'a>,
+
+    /// The `[Control Panel\Desktop]` section.
+    pub desktop: Desktop<'a>,
+}
+
+/// The `[VisualStyles]` section of a `.theme` file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VisualStyles<'a> {
+    /// The path to the `.msstyles` file.
+    pub path: Option<&'a str>,
+
+    /// The color variant of the visual style, such as `NormalColor`.
+    pub color_style: Option<&'a str>,
+
+    /// The size variant of the visual style, such as `NormalSize`.
+    pub size: Option<&'a str>,
+
+    /// The DWM colorization color.
+    pub colorization_color: Option<Rgba>,
+
+    /// Whether window frames are translucent.
+    pub transparency: Option<bool>,
+}
+
+/// The `[Control Panel\Desktop]` section of a `.theme` file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Desktop<'a> {
+    /// The path to the wallpaper image.
+    pub wallpaper: Option<&'a str>,
+
+    /// Whether the wallpaper is tiled.
+    pub tile_wallpaper: Option<bool>,
+
+    /// How the wallpaper is fitted to the screen, as stored in the `WallpaperStyle` value.
+    pub wallpaper_style: Option<u32>,
+
+    /// The legacy desktop pattern.
+    pub pattern: Option<&'a str>,
+}
+
+impl<'a> Theme<'a> {
+    /// Decode the bytes of a `.theme` file into text that can be passed to [`Theme::parse`].
+    ///
+    /// Files starting with a UTF-16LE or UTF-8 byte order mark are decoded accordingly, and the
+    /// mark is dropped. Files without one are ANSI; they are read as UTF-8 when valid, and as
+    /// Latin-1 otherwise, which keeps the ASCII structure intact. Invalid UTF-16 is replaced by
+    /// `U+FFFD`, so decoding only fails if writing to `out` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{SysColor, SysColorIndex, Theme};
+    ///
+    /// let mut bytes = vec![0xFF, 0xFE];
+    /// for unit in "[Control Panel\\Colors]\r\nWindow=1 2 3\r\n".encode_utf16() {
+    ///     bytes.extend_from_slice(&unit.to_le_bytes());
+    /// }
+    ///
+    /// let mut text = String::new();
+    /// Theme::decode(&bytes, &mut text).unwrap();
+    ///
+    /// let theme = Theme::parse(&text).unwrap();
+    /// assert_eq!(theme.palette.get(SysColorIndex::Window), Some(SysColor::from_rgb(1, 2, 3)));
+    /// ```
+    pub fn decode<W: fmt::Write + ?Sized>(bytes: &[u8], out: &mut W) -> fmt::Result {
+        if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            let pairs = rest.chunks_exact(2);
+            let odd = !pairs.remainder().is_empty();
+            let units = pairs.map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+
+            for ch in char::decode_utf16(units) {
+                out.write_char(ch.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+            }
+
+            if odd {
+                out.write_char(char::REPLACEMENT_CHARACTER)?;
+            }
+
+            return Ok(());
+        }
+
+        let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+        match core::str::from_utf8(bytes) {
+            Ok(text) => out.write_str(text),
+            Err(_) => bytes
+                .iter()
+                .try_for_each(|&b| out.write_char(char::from(b))),
+        }
+    }
+
+    /// Parse the text of a `.theme` file.
+    ///
+    /// Section and value names are matched case-insensitively. Colors are looked up with
+    /// [`SysColorIndex::from_name`]; colors this crate does not know, such as
+    /// `ButtonAlternateFace`, are skipped. Unknown sections and values are ignored, and so are
+    /// malformed lines outside of the sections that are read. When a value appears more than
+    /// once, the last one wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{Rgba, SysColor, SysColorIndex, Theme};
+    ///
+    /// let text = "\
+    /// [Theme]
+    /// DisplayName=Corporate
+    ///
+    /// [Control Panel\\Colors]
+    /// ButtonFace=240 240 240
+    /// Hilight=0 120 215
+    ///
+    /// [VisualStyles]
+    /// Path=%SystemRoot%\\resources\\Themes\\Aero\\Aero.msstyles
+    /// ColorizationColor=0XC40078D7
+    /// ";
+    ///
+    /// let theme = Theme::parse(text).unwrap();
+    /// assert_eq!(theme.display_name, Some("Corporate"));
+    /// assert_eq!(theme.palette.get(SysColorIndex::Highlight), Some(SysColor::from_rgb(0, 120, 215)));
+    /// assert_eq!(theme.visual_styles.colorization_color, Some(Rgba::new(0x00, 0x78, 0xD7, 0xC4)));
+    /// ```
+    ///
+    /// Errors report the line they were found on.
+    ///
+    /// ```
+    /// use win_syscolor::{Theme, ThemeErrorKind};
+    ///
+    /// let error = Theme::parse("[Control Panel\\Colors]\nWindow=255 255").unwrap_err();
+    /// assert_eq!(error.line(), 2);
+    /// assert!(matches!(error.kind(), ThemeErrorKind::InvalidColor(_)));
+    /// ```
+    pub fn parse(text: &'a str) -> Result<Self, ThemeError> {
+        let mut theme = Theme::default();
+        let mut section = "";
+
+        for (line, raw, parsed) in Lines::new(text) {
+            let error = |kind| ThemeError { line, kind };
+
+            let (key, value) = match parsed {
+                Line::Blank => continue,
+                Line::Section(name) => {
+                    section = name;
+                    continue;
+                }
+                Line::Entry { key, value } => (key, value),
+                Line::UnclosedSection => {
+                    // Only complain if the header may have opened, or interrupts, a section
+                    // that is read.
+                    let header = raw.trim().trim_start_matches('[');
+                    if is_read_section(section) || is_read_section(header) {
+                        return Err(error(ThemeErrorKind::UnclosedSection));
+                    }
+                    continue;
+                }
+                Line::MissingSeparator if is_read_section(section) => {
+                    return Err(error(ThemeErrorKind::MissingSeparator))
+                }
+                Line::MissingSeparator => continue,
+            };
+
+            let is_key = |name: &str| key.eq_ignore_ascii_case(name);
+            let invalid = || error(ThemeErrorKind::InvalidValue);
+
+            if is_section(section, COLORS) {
+                if let Some(index) = SysColorIndex::from_name(key) {
+                    let color = value
+                        .parse::<SysColor>()
+                        .map_err(|e| error(ThemeErrorKind::InvalidColor(e)))?;
+                    theme.palette.set(index, Some(color));
+                }
+            } else if is_section(section, THEME) {
+                if is_key("DisplayName") {
+                    theme.display_name = Some(value);
+                }
+            } else if is_section(section, VISUAL_STYLES) {
+                let styles = &mut theme.visual_styles;
+
+                if is_key("Path") {
+                    styles.path = Some(value);
+                } else if is_key("ColorStyle") {
+                    styles.color_style = Some(value);
+                } else if is_key("Size") {
+                    styles.size = Some(value);
+                } else if is_key("ColorizationColor") {
+                    styles.colorization_color =
+                        Some(Rgba::from_argb(parse_number(value).ok_or_else(invalid)?));
+                } else if is_key("Transparency") {
+                    styles.transparency = Some(parse_bool(value).ok_or_else(invalid)?);
+                }
+            } else if is_section(section, DESKTOP) {
+                let desktop = &mut theme.desktop;
+
+                if is_key("Wallpaper") {
+                    desktop.wallpaper = Some(value);
+                } else if is_key("TileWallpaper") {
+                    desktop.tile_wallpaper = Some(parse_bool(value).ok_or_else(invalid)?);
+                } else if is_key("WallpaperStyle") {
+                    desktop.wallpaper_style = Some(parse_number(value).ok_or_else(invalid)?);
+                } else if is_key("Pattern") {
+                    desktop.pattern = Some(value);
+                }
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+impl SysColorPalette {
+    /// Write the colors in this palette as a `.theme` file.
+    ///
+    /// The present colors are written to the `[Control Panel\Colors]` section under their
+    /// [registry names](SysColorIndex::registry_name). Lines end with `\r\n`, as Windows
+    /// expects.
+    ///
+    /// No [`SysColorIndex`] stands for `ButtonAlternateFace`, so it is passed separately and
+    /// written after the other colors when present. The raw value kept in
+    /// [`AppearanceScheme::button_alternate_face`](crate::AppearanceScheme::button_alternate_face)
+    /// can be converted with [`SysColor::from_colorref`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{SysColor, SysColorIndex, SysColorPalette, Theme};
+    ///
+    /// let mut palette = SysColorPalette::new();
+    /// palette.set(SysColorIndex::Highlight, Some(SysColor::from_rgb(0, 120, 215)));
+    ///
+    /// let mut text = String::new();
+    /// palette.write_theme(Some(SysColor::from_rgb(181, 181, 181)), &mut text).unwrap();
+    ///
+    /// assert!(text.contains("Hilight=0 120 215\r\n"));
+    /// assert!(text.contains("ButtonAlternateFace=181 181 181\r\n"));
+    /// assert_eq!(Theme::parse(&text).unwrap().palette, palette);
+    /// ```
+    pub fn write_theme<W: fmt::Write + ?Sized>(
+        &self,
+        button_alternate_face: Option<SysColor>,
+        out: &mut W,
+    ) -> fmt::Result {
+        let mut colors = *self;
+
+        write!(out, "{}\r\n", COLORS_HEADER)?;
+        write_colors(out, &mut colors)?;
+        if let Some(color) = button_alternate_face {
+            write_color(out, BUTTON_ALTERNATE_FACE, color)?;
+        }
+        write!(out, "\r\n{}", MASTER_THEME_SELECTOR)
+    }
+
+    /// Write an existing `.theme` file with its colors replaced by the ones in this palette.
+    ///
+    /// Colors that are present in this palette replace the matching values of the
+    /// `[Control Panel\Colors]` section, keeping their position, and the rest are added at the
+    /// end of it. The section is created if the file has none. Every other line is copied as
+    /// is, including sections, comments and colors this crate does not know, such as
+    /// `ButtonAlternateFace`. Lines end with `\r\n`, as Windows expects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{SysColor, SysColorIndex, SysColorPalette};
+    ///
+    /// let existing = "\
+    /// [Theme]\r
+    /// DisplayName=Corporate\r
+    /// \r
+    /// [Control Panel\\Colors]\r
+    /// ButtonAlternateFace=0 0 0\r
+    /// Hilight=0 120 215\r
+    /// \r
+    /// [MasterThemeSelector]\r
+    /// MTSM=RJSPBS\r
+    /// ";
+    ///
+    /// let mut palette = SysColorPalette::new();
+    /// palette.set(SysColorIndex::Highlight, Some(SysColor::from_rgb(200, 16, 46)));
+    /// palette.set(SysColorIndex::HotLight, Some(SysColor::from_rgb(200, 16, 46)));
+    ///
+    /// let mut text = String::new();
+    /// palette.update_theme(existing, &mut text).unwrap();
+    ///
+    /// assert_eq!(text, "\
+    /// [Theme]\r
+    /// DisplayName=Corporate\r
+    /// \r
+    /// [Control Panel\\Colors]\r
+    /// ButtonAlternateFace=0 0 0\r
+    /// Hilight=200 16 46\r
+    /// HotTrackingColor=200 16 46\r
+    /// \r
+    /// [MasterThemeSelector]\r
+    /// MTSM=RJSPBS\r
+    /// ");
+    /// ```
+    pub fn update_theme<W: fmt::Write + ?Sized>(&self, existing: &str, out: &mut W) -> fmt::Result {
+        let mut pending = *self;
+        let mut in_colors = false;
+        let mut found_colors = false;
+
+        // Blank lines at the end of the colors section are held back, so that added colors
+        // are written before them.
+        let mut blanks = 0;
+
+        for (_, raw, line) in Lines::new(existing) {
+            if in_colors {
+                match line {
+                    Line::Blank if raw.trim().is_empty() => {
+                        blanks += 1;
+                        continue;
+                    }
+                    Line::Section(_) => write_colors(out, &mut pending)?,
+                    _ => {}
+                }
+
+                for _ in 0..core::mem::take(&mut blanks) {
+                    out.write_str("\r\n")?;
+                }
+
+                if let Line::Entry { key, .. } = line {
+                    if let Some(index) = SysColorIndex::from_name(key) {
+                        if let Some(color) = pending.remove(index) {
+                            write_color(out, key, color)?;
+                            continue;
+                        } else if self.is_present(index) {
+                            // This color was already written.
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            if let Line::Section(name) = line {
+                in_colors = is_section(name, COLORS);
+                found_colors |= in_colors;
+            }
+
+            write!(out, "{}\r\n", raw)?;
+        }
+
+        if in_colors {
+            write_colors(out, &mut pending)?;
+
+            for _ in 0..blanks {
+                out.write_str("\r\n")?;
+            }
+        } else if !found_colors && pending.iter().any(|(_, color)| color.is_some()) {
+            if !existing.is_empty() {
+                out.write_str("\r\n")?;
+            }
+
+            write!(out, "{}\r\n", COLORS_HEADER)?;
+            write_colors(out, &mut pending)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when parsing a `.theme` file fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ThemeError {
+    line: usize,
+    kind: ThemeErrorKind,
+}
+
+impl ThemeError {
+    /// Get the one-based number of the line where the error was found.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the reason that parsing failed.
+    pub fn kind(&self) -> ThemeErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            ThemeErrorKind::UnclosedSection => f.write_str("section header is missing `]`"),
+            ThemeErrorKind::MissingSeparator => f.write_str("expected `name=value`"),
+            ThemeErrorKind::InvalidColor(error) => write!(f, "invalid color: {}", error),
+            ThemeErrorKind::InvalidValue => f.write_str("invalid value"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ThemeErrorKind::InvalidColor(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The reason that parsing a `.theme` file failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ThemeErrorKind {
+    /// A section header did not end with `]`.
+    UnclosedSection,
+
+    /// A line was neither a section header nor a `name=value` entry.
+    MissingSeparator,
+
+    /// A color in the `[Control Panel\Colors]` section could not be parsed.
+    InvalidColor(ParseColorError),
+
+    /// A numeric or boolean value could not be parsed.
+    InvalidValue,
+}
+
+/// Tell whether a section is one that [`Theme::parse`] reads.
+fn is_read_section(section: &str) -> bool {
+    [COLORS, THEME, VISUAL_STYLES, DESKTOP]
+        .iter()
+        .any(|name| is_section(section, name))
+}
+
+/// Parse a decimal number, or a hexadecimal one prefixed by `0x`.
+fn parse_number(value: &str) -> Option<u32> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(digits) => u32::from_str_radix(digits, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Parse a boolean stored as `0` or `1`.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// Write a single color entry.
+fn write_color<W: fmt::Write + ?Sized>(out: &mut W, key: &str, color: SysColor) -> fmt::Result {
+    write!(
+        out,
+        "{}={} {} {}\r\n",
+        key,
+        color.red(),
+        color.green(),
+        color.blue()
+    )
+}
+
+/// Write every color left in the palette under its registry name, removing it.
+fn write_colors<W: fmt::Write + ?Sized>(out: &mut W, palette: &mut SysColorPalette) -> fmt::Result {
+    for &index in SysColorIndex::ALL {
+        if let Some(color) = palette.remove(index) {
+            write_color(out, index.registry_name(), color)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Theme, ThemeErrorKind};
+    use crate::{SysColor, SysColorIndex};
+
+    #[cfg(feature = "std")]
+    use std::string::String;
+
+    #[cfg(feature = "std")]
+    fn decode(bytes: &[u8]) -> String {
+        let mut text = String::new();
+        Theme::decode(bytes, &mut text).unwrap();
+        text
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decodes_byte_order_marks_and_ansi() {
+        assert_eq!(decode(b"\xFF\xFEA\0b\0"), "Ab");
+        assert_eq!(decode(b"\xEF\xBB\xBFFor\xC3\xAAt"), "For\u{ea}t");
+        assert_eq!(decode(b"For\xEAt"), "For\u{ea}t");
+
+        // A lone surrogate and a trailing odd byte are replaced.
+        assert_eq!(decode(b"\xFF\xFEA\0\x00\xD8B"), "A\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn skips_malformed_lines_in_unknown_sections() {
+        let theme = Theme::parse(
+            "[Other]\ngarbage line\n[Unclosed\n[Control Panel\\Colors]\nWindow=1 2 3\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            theme.palette.get(SysColorIndex::Window),
+            Some(SysColor::from_rgb(1, 2, 3))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lines_in_read_sections() {
+        let error = Theme::parse("[Control Panel\\Colors]\ngarbage line\n").unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.kind(), ThemeErrorKind::MissingSeparator);
+
+        let error = Theme::parse("[Theme]\n[Other\n").unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.kind(), ThemeErrorKind::UnclosedSection);
+
+        let error = Theme::parse("[Other]\n[VisualStyles\n").unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.kind(), ThemeErrorKind::UnclosedSection);
+    }
+}
@@ -0,0 +1,351 @@
+// Boost/Apache2 License
+
+//! Reading `.themepack` and `.deskthemepack` archives.
+
+use crate::ini::{ends_with_ignore_case, is_section, Line, Lines};
+use crate::theme::{DESKTOP, VISUAL_STYLES};
+use crate::{Theme, ThemeError};
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+use std::io::{self, Read, Seek};
+use std::string::String;
+use std::vec::Vec;
+
+/// A theme pack, as stored in `.themepack` and `.deskthemepack` files.
+///
+/// Theme packs are cabinet archives holding a `.theme` file along with the wallpapers and other
+/// files it uses.
+#[derive(Debug, Clone)]
+pub struct ThemePack {
+    theme_name: String,
+    text: String,
+    files: Vec<String>,
+}
+
+impl ThemePack {
+    /// Read a theme pack from a cabinet archive.
+    ///
+    /// The first `.theme` file in the archive is decoded with [`Theme::decode`] and parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use win_syscolor::{SysColorIndex, ThemePack};
+    ///
+    /// let pack = ThemePack::read(File::open("Forest.deskthemepack")?)?;
+    /// let theme = pack.theme();
+    ///
+    /// println!("{:?} is the window color", theme.palette.get(SysColorIndex::Window));
+    ///
+    /// for asset in pack.assets() {
+    ///     println!("{} is used by {}", asset, pack.theme_name());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, ThemePackError> {
+        let mut cabinet = cab::Cabinet::new(reader)?;
+
+        let files: Vec<String> = cabinet
+            .folder_entries()
+            .flat_map(|folder| folder.file_entries())
+            .map(|file| String::from(file.name()))
+            .collect();
+
+        let theme_name = files
+            .iter()
+            .find(|name| is_theme_file(name))
+            .ok_or(ThemePackError::MissingTheme)?
+            .clone();
+
+        let mut bytes = Vec::new();
+        cabinet.read_file(&theme_name)?.read_to_end(&mut bytes)?;
+        let mut text = String::new();
+        Theme::decode(&bytes, &mut text).expect("writing to a string cannot fail");
+        Theme::parse(&text).map_err(ThemePackError::Theme)?;
+
+        Ok(ThemePack {
+            theme_name,
+            text,
+            files,
+        })
+    }
+
+    /// Get the name of the `.theme` file within the archive.
+    pub fn theme_name(&self) -> &str {
+        &self.theme_name
+    }
+
+    /// Get the text of the `.theme` file.
+    pub fn theme_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the parsed `.theme` file.
+    pub fn theme(&self) -> Theme<'_> {
+        Theme::parse(&self.text).expect("theme was checked when the pack was read")
+    }
+
+    /// Get the names of every file in the archive, including the `.theme` file.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Iterate over the paths of the files that the theme uses.
+    ///
+    /// These are the wallpaper, the slideshow folder, the visual style, the screen saver,
+    /// cursors, sounds and icons, in the order they appear in the `.theme` file. Paths are returned exactly as
+    /// written, so they may contain environment variables such as `%SystemRoot%`, resource
+    /// indices such as `,-109`, and duplicates. Files shipped in the pack itself are usually
+    /// referenced relative to `%ThemeDir%`.
+    pub fn assets(&self) -> Assets<'_> {
+        Assets {
+            lines: Lines::new(&self.text),
+            section: "",
+        }
+    }
+}
+
+/// An iterator over the files that a theme uses.
+///
+/// This is returned by [`ThemePack::assets`].
+pub struct Assets<'a> {
+    lines: Lines<'a>,
+    section: &'a str,
+}
+
+impl fmt::Debug for Assets<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Assets")
+            .field("section", &self.section)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> Iterator for Assets<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (_, _, line) in &mut self.lines {
+            match line {
+                Line::Section(name) => self.section = name.trim(),
+                Line::Entry { key, value } if !value.is_empty() && is_asset(self.section, key) => {
+                    return Some(value);
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.lines.size_hint().1)
+    }
+}
+
+impl FusedIterator for Assets<'_> {}
+
+/// The error returned when reading a theme pack fails.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ThemePackError {
+    /// The archive could not be read.
+    Io(io::Error),
+
+    /// The archive does not contain a `.theme` file.
+    MissingTheme,
+
+    /// The `.theme` file could not be parsed.
+    Theme(ThemeError),
+}
+
+impl From<io::Error> for ThemePackError {
+    fn from(error: io::Error) -> Self {
+        ThemePackError::Io(error)
+    }
+}
+
+impl fmt::Display for ThemePackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemePackError::Io(error) => write!(f, "failed to read theme pack: {}", error),
+            ThemePackError::MissingTheme => f.write_str("theme pack contains no .theme file"),
+            ThemePackError::Theme(error) => write!(f, "invalid .theme file: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ThemePackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThemePackError::Io(error) => Some(error),
+            ThemePackError::Theme(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Tell whether a file in the archive is a `.theme` file.
+fn is_theme_file(name: &str) -> bool {
+    ends_with_ignore_case(name, ".theme")
+}
+
+/// Tell whether a value of a `.theme` file names a file that the theme uses.
+fn is_asset(section: &str, key: &str) -> bool {
+    let ends_with = |suffix: &str| ends_with_ignore_case(section, suffix);
+    let is_key = |name: &str| key.eq_ignore_ascii_case(name);
+
+    if is_section(section, DESKTOP) {
+        is_key("Wallpaper")
+    } else if is_section(section, VISUAL_STYLES) {
+        is_key("Path")
+    } else if is_section(section, "Slideshow") {
+        is_key("ImagesRootPath")
+    } else if is_section(section, "boot") {
+        // The screen saver.
+        is_key("SCRNSAVE.EXE")
+    } else if is_section(section, r"Control Panel\Cursors") {
+        // The other values name cursor files.
+        !is_key("DefaultValue") && !is_key("Scheme Source")
+    } else if ends_with(".Current") {
+        // Sound events, under `[AppEvents\Schemes\Apps\...\.Current]`.
+        is_key("DefaultValue")
+    } else {
+        // Icons, under `[CLSID\{...}\DefaultIcon]`, including the full and empty recycle bin.
+        ends_with(r"\DefaultIcon")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ThemePack, ThemePackError};
+    use crate::{SysColor, SysColorIndex};
+
+    use core::convert::TryFrom;
+    use std::io::{Cursor, Write};
+    use std::vec::Vec;
+
+    const THEME: &str = "\
+[Theme]\r
+DisplayName=Forêt\r
+\r
+[Control Panel\\Desktop]\r
+Wallpaper=%ThemeDir%DesktopBackground\\forest.jpg\r
+TileWallpaper=0\r
+\r
+[Control Panel\\Cursors]\r
+DefaultValue=Windows Aero\r
+Arrow=%SystemRoot%\\cursors\\aero_arrow.cur\r
+\r
+[AppEvents\\Schemes\\Apps\\.Default\\.Default\\.Current]\r
+DefaultValue=%SystemRoot%\\media\\Windows Background.wav\r
+\r
+[CLSID\\{645FF040-5081-101B-9F08-00AA002F954E}\\DefaultIcon]\r
+Full=%SystemRoot%\\System32\\imageres.dll,-54\r
+\r
+[boot]\r
+SCRNSAVE.EXE=%SystemRoot%\\system32\\Mystify.scr\r
+\r
+[Control Panel\\Colors]\r
+Window=1 2 3\r
+\r
+[Slideshow]\r
+ImagesRootPath=%ThemeDir%DesktopBackground\r
+Interval=60000\r
+";
+
+    /// Build a cabinet archive holding the given files.
+    fn build_pack(files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+        let mut builder = cab::CabinetBuilder::new();
+        {
+            let folder = builder.add_folder(cab::CompressionType::MsZip);
+            for (name, _) in files {
+                folder.add_file(*name);
+            }
+        }
+
+        let mut writer = builder.build(Cursor::new(Vec::new())).unwrap();
+        while let Some(mut file) = writer.next_file().unwrap() {
+            let (_, contents) = files
+                .iter()
+                .find(|(name, _)| *name == file.file_name())
+                .unwrap();
+            file.write_all(contents).unwrap();
+        }
+
+        let mut cabinet = writer.finish().unwrap();
+        cabinet.set_position(0);
+        cabinet
+    }
+
+    /// Build a theme pack holding the given `.theme` bytes and a wallpaper.
+    fn build_theme_pack(theme: &[u8]) -> Cursor<Vec<u8>> {
+        build_pack(&[
+            ("DesktopBackground\\forest.jpg", b"not really a jpeg"),
+            ("Forest.THEME", theme),
+        ])
+    }
+
+    /// Check a pack built by `build_theme_pack` from `THEME`.
+    fn check_pack(pack: &ThemePack) {
+        assert_eq!(pack.theme_name(), "Forest.THEME");
+        assert_eq!(
+            pack.files(),
+            ["DesktopBackground\\forest.jpg", "Forest.THEME"]
+        );
+
+        let theme = pack.theme();
+        assert_eq!(theme.display_name, Some("Forêt"));
+        assert_eq!(
+            theme.palette.get(SysColorIndex::Window),
+            Some(SysColor::from_rgb(1, 2, 3))
+        );
+
+        let assets: Vec<&str> = pack.assets().collect();
+        assert_eq!(
+            assets,
+            [
+                "%ThemeDir%DesktopBackground\\forest.jpg",
+                "%SystemRoot%\\cursors\\aero_arrow.cur",
+                "%SystemRoot%\\media\\Windows Background.wav",
+                "%SystemRoot%\\System32\\imageres.dll,-54",
+                "%SystemRoot%\\system32\\Mystify.scr",
+                "%ThemeDir%DesktopBackground",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_utf16_theme() {
+        let mut bytes = std::vec![0xFF, 0xFE];
+        for unit in THEME.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+
+        check_pack(&ThemePack::read(build_theme_pack(&bytes)).unwrap());
+    }
+
+    #[test]
+    fn reads_ansi_theme() {
+        // "Forêt" in Windows-1252, which is not valid UTF-8.
+        let bytes: Vec<u8> = THEME
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)).unwrap())
+            .collect();
+
+        check_pack(&ThemePack::read(build_theme_pack(&bytes)).unwrap());
+    }
+
+    #[test]
+    fn rejects_pack_without_theme() {
+        let cabinet = build_pack(&[("forest.jpg", b"not really a jpeg")]);
+
+        assert!(matches!(
+            ThemePack::read(cabinet),
+            Err(ThemePackError::MissingTheme)
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+// Boost/Apache2 License
+
+//! Simulation of color vision deficiencies.
+
+use crate::{space, ContrastPair, LinearRgb, Oklab, SysColor, SysColorPalette};
+
+use core::iter::FusedIterator;
+use core::slice;
+
+/// A color vision deficiency.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorDeficiency {
+    /// Missing or anomalous long-wavelength (red) cones.
+    Protanopia,
+
+    /// Missing or anomalous medium-wavelength (green) cones.
+    Deuteranopia,
+
+    /// Missing or anomalous short-wavelength (blue) cones.
+    Tritanopia,
+
+    /// No color perception at all.
+    Achromatopsia,
+}
+
+impl ColorDeficiency {
+    /// Every simulated color vision deficiency.
+    pub const ALL: &'static [ColorDeficiency] = &[
+        ColorDeficiency::Protanopia,
+        ColorDeficiency::Deuteranopia,
+        ColorDeficiency::Tritanopia,
+        ColorDeficiency::Achromatopsia,
+    ];
+
+    /// Get the matrix that simulates the full deficiency in linear-light sRGB.
+    ///
+    /// The dichromacy matrices are those of Machado, Oliveira and Fernandes (2009) at a severity
+    /// of 1.0. Achromatopsia maps every color to its relative luminance.
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorDeficiency::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            ColorDeficiency::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            ColorDeficiency::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+            ColorDeficiency::Achromatopsia => [[0.212_6, 0.715_2, 0.072_2]; 3],
+        }
+    }
+}
+
+impl SysColor {
+    /// Simulate how this color appears to someone with a color vision deficiency.
+    ///
+    /// `severity` ranges from `0.0`, which returns the color unchanged, to `1.0`, which simulates
+    /// the complete deficiency. Intermediate severities interpolate between the two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorDeficiency, SysColor};
+    ///
+    /// let red = SysColor::from_rgb(255, 0, 0);
+    /// let green = SysColor::from_rgb(0, 160, 0);
+    /// assert!(red.oklab_distance(green) > 0.3);
+    ///
+    /// assert_eq!(red.simulate(ColorDeficiency::Protanopia, 0.0), red);
+    ///
+    /// let gray = red.simulate(ColorDeficiency::Achromatopsia, 1.0);
+    /// assert_eq!(gray.red(), gray.green());
+    /// assert_eq!(gray.green(), gray.blue());
+    ///
+    /// let red = red.simulate(ColorDeficiency::Deuteranopia, 1.0);
+    /// let green = green.simulate(ColorDeficiency::Deuteranopia, 1.0);
+    /// assert!(red.oklab_distance(green) < 0.1);
+    /// ```
+    pub fn simulate(self, deficiency: ColorDeficiency, severity: f32) -> SysColor {
+        let severity = space::clamp(severity);
+
+        let LinearRgb { red, green, blue } = self.into();
+        let input = [red, green, blue];
+        let matrix = deficiency.matrix();
+
+        let mut output = [0.0; 3];
+        for ((out, row), &original) in output.iter_mut().zip(&matrix).zip(&input) {
+            let simulated = row[0] * red + row[1] * green + row[2] * blue;
+            *out = original + (simulated - original) * severity;
+        }
+
+        LinearRgb {
+            red: output[0],
+            green: output[1],
+            blue: output[2],
+        }
+        .into()
+    }
+
+    /// Get the Euclidean distance between this color and another one in Oklab.
+    ///
+    /// Differences below about `0.02` are hard to notice.
+    pub fn oklab_distance(self, other: SysColor) -> f32 {
+        let (a, b) = (Oklab::from(self), Oklab::from(other));
+        let (dl, da, db) = (a.l - b.l, a.a - b.a, a.b - b.b);
+        libm::sqrtf(dl * dl + da * da + db * db)
+    }
+}
+
+impl SysColorPalette {
+    /// Simulate how every color in this palette appears to someone with a color vision
+    /// deficiency.
+    ///
+    /// See [`SysColor::simulate`] for the meaning of `severity`.
+    pub fn simulate(&self, deficiency: ColorDeficiency, severity: f32) -> SysColorPalette {
+        let mut palette = *self;
+
+        for (index, color) in self {
+            if let Some(color) = color {
+                palette.set(index, Some(color.simulate(deficiency, severity)));
+            }
+        }
+
+        palette
+    }
+
+    /// Iterate over the pairs in [`ContrastPair::ALL`] that become hard to tell apart under a
+    /// color vision deficiency.
+    ///
+    /// Each item is a pair along with the Oklab distance between its simulated colors, as
+    /// measured by [`SysColor::oklab_distance`]. Pairs whose distance is below `min_distance` are
+    /// reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use win_syscolor::{ColorDeficiency, ColorScheme};
+    ///
+    /// let palette = ColorScheme::Windows10.palette();
+    /// let pairs = palette.indistinguishable_pairs(ColorDeficiency::Achromatopsia, 1.0, 0.1);
+    /// assert_eq!(pairs.count(), 0);
+    /// ```
+    pub fn indistinguishable_pairs(
+        &self,
+        deficiency: ColorDeficiency,
+        severity: f32,
+        min_distance: f32,
+    ) -> IndistinguishablePairs {
+        IndistinguishablePairs {
+            palette: self.simulate(deficiency, severity),
+            pairs: ContrastPair::ALL.iter(),
+            min_distance,
+        }
+    }
+}
+
+/// An iterator over the pairs of a palette that are hard to tell apart.
+///
+/// This is returned by [`SysColorPalette::indistinguishable_pairs`].
+#[derive(Debug, Clone)]
+pub struct IndistinguishablePairs {
+    palette: SysColorPalette,
+    pairs: slice::Iter<'static, ContrastPair>,
+    min_distance: f32,
+}
+
+impl Iterator for IndistinguishablePairs {
+    type Item = (ContrastPair, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (palette, min_distance) = (&self.palette, self.min_distance);
+
+        self.pairs.by_ref().find_map(|&pair| {
+            let foreground = palette.get(pair.foreground)?;
+            let background = palette.get(pair.background)?;
+            let distance = foreground.oklab_distance(background);
+
+            if distance < min_distance {
+                Some((pair, distance))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pairs.size_hint().1)
+    }
+}
+
+impl FusedIterator for IndistinguishablePairs {}